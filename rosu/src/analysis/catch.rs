@@ -0,0 +1,123 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+
+use crate::catch_convert::{get_catch_objects, CatchObject};
+use crate::encoding::read_map_bytes;
+use crate::java::Result;
+use crate::pp::get_map_attr;
+use crate::StatusFlag;
+
+/// 统计接盘移动距离的区间长度 (ms, 实际时间)
+const MOVEMENT_SECTION: f64 = 1000.0;
+
+/// catch 谱面的物件与 hyperdash 统计, 支持转谱, HR / EZ 影响接盘大小, HR 会偏移水果位置, 时间已按倍速换算
+///
+/// ` [(catch)u8 | (fruits, droplets, tiny droplets, bananas)i32 * 4 | (catcher width)f32 | (hyperdash size)i32 | hyperdash * size | (total movement)f64 | (section)f64 | (size)i32 | (movement)f64 * size] `
+/// - hyperdash: `[(time)f64 | (x)f32 | (target time)f64 | (target x)f32]`, 从该物件冲到下一个物件
+/// - movement: 依次接住每个水果与 droplet 所需的横向移动距离, 从第一个物件开始每 `section` 统计一次
+pub fn catch_statistics(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    attr: &JByteArray,
+) -> Result<Vec<u8>> {
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let attr = get_map_attr(env, attr)?;
+    let catch = get_catch_objects(&map_bytes, &attr)?;
+    let clock_rate = attr.clock_rate();
+    let objects = &catch.objects;
+
+    let hyper_dashes: Vec<usize> = (0..objects.len().saturating_sub(1))
+        .filter(|i| objects[*i].hyper_dash)
+        .collect();
+
+    let (total_movement, sections) = movement(objects, clock_rate);
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::Catch.bits());
+    result.put_i32(catch.fruits as i32);
+    result.put_i32(catch.droplets as i32);
+    result.put_i32(catch.tiny_droplets as i32);
+    result.put_i32(catch.bananas as i32);
+    result.put_f32(catch.catcher_width);
+    result.put_i32(hyper_dashes.len() as i32);
+    for i in hyper_dashes {
+        let (curr, next) = (&objects[i], &objects[i + 1]);
+        result.put_f64(curr.start_time / clock_rate);
+        result.put_f32(curr.effective_x());
+        result.put_f64(next.start_time / clock_rate);
+        result.put_f32(next.effective_x());
+    }
+    result.put_f64(total_movement);
+    result.put_f64(MOVEMENT_SECTION);
+    result.put_i32(sections.len() as i32);
+    for movement in sections {
+        result.put_f64(movement);
+    }
+    Ok(result)
+}
+
+/// 总移动距离, 以及从第一个物件开始每 [`MOVEMENT_SECTION`] (实际时间) 的移动距离
+fn movement(objects: &[CatchObject], clock_rate: f64) -> (f64, Vec<f64>) {
+    let start_time = objects.first().map_or(0.0, |h| h.start_time / clock_rate);
+    let mut total_movement = 0.0;
+    let mut sections = Vec::<f64>::new();
+    for pair in objects.windows(2) {
+        let distance = f64::from((pair[1].effective_x() - pair[0].effective_x()).abs());
+        let section = ((pair[1].start_time / clock_rate - start_time) / MOVEMENT_SECTION) as usize;
+        if sections.len() <= section {
+            sections.resize(section + 1, 0.0);
+        }
+        sections[section] += distance;
+        total_movement += distance;
+    }
+    (total_movement, sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use rosu_map::section::general::GameMode;
+
+    use super::*;
+    use crate::pp::JniMapAttr;
+
+    const MAP: &str = "osu file format v14
+
+[General]
+Mode: 2
+
+[Difficulty]
+CircleSize:5
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+100,192,0,1,0,0:0:0:0:
+300,192,500,1,0,0:0:0:0:
+100,192,1200,1,0,0:0:0:0:
+200,192,2500,1,0,0:0:0:0:
+500,192,2600,1,0,0:0:0:0:
+";
+
+    fn objects() -> Vec<CatchObject> {
+        let attr = JniMapAttr {
+            mode: Some(GameMode::Catch),
+            ..Default::default()
+        };
+        get_catch_objects(MAP.as_bytes(), &attr).unwrap().objects
+    }
+
+    #[test]
+    fn hyper_dash() {
+        let hyper_dashes: Vec<bool> = objects().iter().map(|h| h.hyper_dash).collect();
+        assert_eq!(hyper_dashes, vec![false, false, false, true, false]);
+    }
+
+    #[test]
+    fn movement_sections() {
+        let objects = objects();
+        assert_eq!(movement(&objects, 1.0), (800.0, vec![200.0, 200.0, 400.0]));
+        assert_eq!(movement(&objects, 2.0), (800.0, vec![400.0, 400.0]));
+    }
+}
@@ -0,0 +1,140 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_pp::Beatmap;
+
+use crate::java::{Error, Result};
+use crate::mode_flag;
+use crate::pp::get_map_and_attr;
+
+/// 最短的分段长度 (ms), 过短时分段数量会过多
+const MIN_WINDOW: f64 = 1.0;
+
+/// 每秒物件数 (nps), 按 `window` (ms, 实际时间) 分段统计, 时间已按倍速换算
+///
+/// ` [(mode)u8 | (window)f64 | (peak nps, average nps)f64 * 2 | (start time)f64 | (size)i32 | (nps)f64 * size] `
+///
+/// 峰值为任意 `window` 长度区间内的最大值, 分段从第一个物件开始
+pub fn note_density(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    attr: &JByteArray,
+    window: f64,
+) -> Result<Vec<u8>> {
+    if !window.is_finite() || window < MIN_WINDOW {
+        return Err(Error::from(format!(
+            "window must be at least {MIN_WINDOW}ms"
+        )));
+    }
+
+    let (map, attr) = get_map_and_attr(env, local_map, attr)?;
+    let Density {
+        start_time,
+        peak,
+        average,
+        sections,
+    } = density(&map, attr.clock_rate(), window);
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(mode_flag(map.mode).bits());
+    result.put_f64(window);
+    result.put_f64(peak);
+    result.put_f64(average);
+    result.put_f64(start_time);
+    result.put_i32(sections.len() as i32);
+    for nps in sections {
+        result.put_f64(nps);
+    }
+    Ok(result)
+}
+
+struct Density {
+    start_time: f64,
+    peak: f64,
+    average: f64,
+    /// 每个分段的 nps
+    sections: Vec<f64>,
+}
+
+fn density(map: &Beatmap, clock_rate: f64, window: f64) -> Density {
+    let times: Vec<f64> = map
+        .hit_objects
+        .iter()
+        .map(|h| h.start_time / clock_rate)
+        .collect();
+    let window_secs = window / 1000.0;
+
+    let start_time = times.first().copied().unwrap_or(0.0);
+    let last_time = times.last().copied().unwrap_or(0.0);
+
+    let mut sections = if times.is_empty() {
+        Vec::new()
+    } else {
+        vec![0usize; ((last_time - start_time) / window) as usize + 1]
+    };
+    for time in &times {
+        sections[((time - start_time) / window) as usize] += 1;
+    }
+
+    let mut peak = 0;
+    let mut head = 0;
+    for (i, time) in times.iter().enumerate() {
+        while time - times[head] >= window {
+            head += 1;
+        }
+        peak = peak.max(i - head + 1);
+    }
+
+    let average = if last_time > start_time {
+        times.len() as f64 / ((last_time - start_time) / 1000.0)
+    } else {
+        0.0
+    };
+
+    Density {
+        start_time,
+        peak: peak as f64 / window_secs,
+        average,
+        sections: sections
+            .into_iter()
+            .map(|count| count as f64 / window_secs)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 前两秒每 250ms 一个物件, 之后每 500ms 一个
+    fn map() -> Beatmap {
+        let mut text = String::from(
+            "osu file format v14\n\n[General]\nMode: 0\n\n[TimingPoints]\n0,250,4,2,0,100,1,0\n\n[HitObjects]\n",
+        );
+        let times = (0..8)
+            .map(|i| 1000 + i * 250)
+            .chain((0..4).map(|i| 3000 + i * 500));
+        for time in times {
+            text.push_str(&format!("256,192,{time},1,0,0:0:0:0:\n"));
+        }
+        Beatmap::from_bytes(text.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn sections_and_peak() {
+        let density = density(&map(), 1.0, 1000.0);
+        assert_eq!(density.start_time, 1000.0);
+        assert_eq!(density.sections, vec![4.0, 4.0, 2.0, 2.0]);
+        assert_eq!(density.peak, 4.0);
+        assert_eq!(density.average, 12.0 / 3.5);
+    }
+
+    #[test]
+    fn clock_rate() {
+        let density = density(&map(), 2.0, 1000.0);
+        assert_eq!(density.start_time, 500.0);
+        assert_eq!(density.sections, vec![8.0, 4.0]);
+        assert_eq!(density.peak, 8.0);
+        assert_eq!(density.average, 12.0 / 1.75);
+    }
+}
@@ -0,0 +1,225 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_pp::model::hit_object::HitObjectKind;
+use rosu_pp::model::mode::GameMode;
+use rosu_pp::Beatmap;
+
+use crate::java::{Error, Result};
+use crate::pp::get_map_and_attr;
+use crate::StatusFlag;
+
+/// mania 谱面的按键统计, 支持转谱, 时间已按倍速换算
+///
+/// ` [(mania)u8 | (keys)i32 | (count)i32 * keys | (notes, long notes)i32 * 2 | (ln ratio, average ln length)f64 * 2 | (rows, chords, jacks)i32 * 3 | (chord ratio, average row size, jack ratio)f64 * 3 | (chords per second, jacks per second)f64 * 2 | (left, right, middle)i32 * 3] `
+///
+/// - row: 同一时刻的所有物件, chord 为至少两个物件的 row
+/// - jack: 同一列在相邻的两个 row 中都有物件
+/// - left / right: 左右手各自负责的列的物件数, 奇数键时中间一列单独计入 middle
+pub fn mania_statistics(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    attr: &JByteArray,
+) -> Result<Vec<u8>> {
+    let (map, attr) = get_map_and_attr(env, local_map, attr)?;
+    if map.mode != GameMode::Mania {
+        return Err(Error::from("only mania beatmap is supported"));
+    }
+    let clock_rate = attr.clock_rate();
+    let Mania {
+        columns,
+        total,
+        long_notes,
+        ln_length,
+        rows,
+        chords,
+        jacks,
+        duration,
+    } = mania(&map, clock_rate);
+    let (left, right, middle) = hands(&columns);
+
+    let ratio = |count: usize, total: usize| {
+        if total > 0 {
+            count as f64 / total as f64
+        } else {
+            0.0
+        }
+    };
+    let per_second = |count: usize| {
+        if duration > 0.0 {
+            count as f64 / duration
+        } else {
+            0.0
+        }
+    };
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::Mania.bits());
+    result.put_i32(columns.len() as i32);
+    for count in &columns {
+        result.put_i32(*count);
+    }
+    result.put_i32(total as i32);
+    result.put_i32(long_notes as i32);
+    result.put_f64(ratio(long_notes, total));
+    result.put_f64(if long_notes > 0 {
+        ln_length / long_notes as f64
+    } else {
+        0.0
+    });
+    result.put_i32(rows as i32);
+    result.put_i32(chords as i32);
+    result.put_i32(jacks as i32);
+    result.put_f64(ratio(chords, rows));
+    result.put_f64(ratio(total, rows));
+    result.put_f64(ratio(jacks, total));
+    result.put_f64(per_second(chords));
+    result.put_f64(per_second(jacks));
+    result.put_i32(left);
+    result.put_i32(right);
+    result.put_i32(middle);
+    Ok(result)
+}
+
+struct Mania {
+    /// 每一列的物件数
+    columns: Vec<i32>,
+    total: usize,
+    long_notes: usize,
+    /// 长条的总长度 (ms)
+    ln_length: f64,
+    rows: usize,
+    chords: usize,
+    jacks: usize,
+    /// 第一个到最后一个物件的时长 (s)
+    duration: f64,
+}
+
+fn mania(map: &Beatmap, clock_rate: f64) -> Mania {
+    let keys = map.cs.round().max(1.0);
+    let column_width = 512.0 / keys;
+    let keys = keys as usize;
+
+    let mut columns = vec![0; keys];
+    let mut long_notes = 0_usize;
+    let mut ln_length = 0.0;
+    // (时间, 列)
+    let mut notes = Vec::with_capacity(map.hit_objects.len());
+    for h in &map.hit_objects {
+        let column = ((h.pos.x / column_width).floor() as usize).min(keys - 1);
+        columns[column] += 1;
+        if let HitObjectKind::Hold(ref hold) = h.kind {
+            long_notes += 1;
+            ln_length += hold.duration / clock_rate;
+        }
+        notes.push((h.start_time, column));
+    }
+    notes.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    let mut rows = 0_usize;
+    let mut chords = 0_usize;
+    let mut jacks = 0_usize;
+    let mut prev_row = vec![false; keys];
+    let mut i = 0;
+    while i < notes.len() {
+        let mut row = vec![false; keys];
+        let mut size = 0;
+        let time = notes[i].0;
+        while i < notes.len() && notes[i].0 == time {
+            row[notes[i].1] = true;
+            size += 1;
+            i += 1;
+        }
+
+        rows += 1;
+        if size >= 2 {
+            chords += 1;
+        }
+        jacks += row
+            .iter()
+            .zip(&prev_row)
+            .filter(|(a, b)| **a && **b)
+            .count();
+        prev_row = row;
+    }
+
+    let duration = match (notes.first(), notes.last()) {
+        (Some((first, _)), Some((last, _))) => (last - first) / clock_rate / 1000.0,
+        _ => 0.0,
+    };
+
+    Mania {
+        columns,
+        total: notes.len(),
+        long_notes,
+        ln_length,
+        rows,
+        chords,
+        jacks,
+        duration,
+    }
+}
+
+/// 左右手各自负责的列的物件数, 奇数键时中间一列单独计算
+fn hands(columns: &[i32]) -> (i32, i32, i32) {
+    let keys = columns.len();
+    let half = keys / 2;
+    let left = columns[..half].iter().sum();
+    let right = columns[keys - half..].iter().sum();
+    let middle = columns[half..keys - half].iter().sum();
+    (left, right, middle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 5K, 两个 chord, 两个 jack, 中间一列是长条
+    const MAP: &str = "osu file format v14
+
+[General]
+Mode: 3
+
+[Difficulty]
+CircleSize:5
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+51,192,0,1,0,0:0:0:0:
+153,192,0,1,0,0:0:0:0:
+153,192,500,1,0,0:0:0:0:
+256,192,1000,128,0,1500:0:0:0:0:
+358,192,1500,1,0,0:0:0:0:
+460,192,1500,1,0,0:0:0:0:
+460,192,2000,1,0,0:0:0:0:
+";
+
+    fn map() -> Beatmap {
+        Beatmap::from_bytes(MAP.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn rows_chords_and_jacks() {
+        let mania = mania(&map(), 1.0);
+        assert_eq!(mania.columns, vec![1, 2, 1, 1, 2]);
+        assert_eq!(mania.total, 7);
+        assert_eq!((mania.long_notes, mania.ln_length), (1, 500.0));
+        assert_eq!((mania.rows, mania.chords, mania.jacks), (5, 2, 2));
+        assert_eq!(mania.duration, 2.0);
+        assert_eq!(hands(&mania.columns), (3, 3, 1));
+    }
+
+    #[test]
+    fn clock_rate() {
+        let mania = mania(&map(), 2.0);
+        assert_eq!(mania.ln_length, 250.0);
+        assert_eq!(mania.duration, 1.0);
+    }
+
+    #[test]
+    fn even_keys_have_no_middle() {
+        assert_eq!(hands(&[1, 2, 3, 4]), (3, 7, 0));
+    }
+}
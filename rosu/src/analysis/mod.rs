@@ -0,0 +1,22 @@
+pub use catch::catch_statistics;
+pub use density::note_density;
+pub use mania::mania_statistics;
+pub use pattern::classify_patterns;
+pub use reading::reading_difficulty;
+pub use scroll::scroll_speed;
+pub use sections::hard_sections;
+pub(crate) use snap::nearest_snap;
+pub use snap::rhythm_snap;
+pub use spacing::spacing_statistics;
+pub use taiko::taiko_statistics;
+
+mod catch;
+mod density;
+mod mania;
+mod pattern;
+mod reading;
+mod scroll;
+mod sections;
+mod snap;
+mod spacing;
+mod taiko;
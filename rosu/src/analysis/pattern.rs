@@ -0,0 +1,247 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::timing_points::TimingPoint;
+use rosu_pp::any::DifficultyAttributes;
+use rosu_pp::{Beatmap, Difficulty};
+
+use crate::diagnostics::parse_error;
+use crate::encoding::read_map_bytes;
+use crate::java::Result;
+use crate::osu::{get_osu_objects, OsuObjects};
+use crate::pp::{get_map_attr, JniMapAttr};
+use crate::StatusFlag;
+
+/// 允许的节奏误差, 以拍长为单位
+const RHYTHM_LENIENCY: f64 = 1.1;
+/// 间距达到多少个半径算作跳
+const JUMP_RADII: f32 = 3.0;
+/// 实际间隔不超过该值 (ms) 的 1/2 需要交互
+const ALT_INTERVAL: f64 = 150.0;
+const MIN_BURST: usize = 3;
+const MIN_STREAM: usize = 9;
+
+const STREAM: usize = 0;
+const BURST: usize = 1;
+const JUMP: usize = 2;
+const ALT: usize = 3;
+const SLIDER: usize = 4;
+const OTHER: usize = 5;
+
+/// osu! 谱面的 pattern 分类, 按物件统计占比
+///
+/// ` [(osu)u8 | (stream, burst, jump, alt, slider, other)f64 * 6 | (longest stream)i32 | (stream count, burst count)i32 * 2 | (aim, speed, aim ratio)f64 * 3] `
+///
+/// - stream / burst: 连续 1/4 (或更快) 的圆圈, 至少 9 个为 stream, 3 到 8 个为 burst
+/// - longest stream: 最长 stream 的物件数, burst 不计入, 没有 stream 时为 0
+/// - jump: 1/2 (或更快) 且间距不小于 3 个半径
+/// - alt: 实际间隔不超过 150ms 的近距离 1/2
+/// - aim ratio: `aim / (aim + speed)`, 大于 0.5 偏向 aim, 反之偏向 speed
+pub fn classify_patterns(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    attr: &JByteArray,
+) -> Result<Vec<u8>> {
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let attr = get_map_attr(env, attr)?;
+    let osu = get_osu_objects(&map_bytes, &attr)?;
+    let patterns = Patterns::new(&osu, attr.clock_rate());
+    let (aim, speed) = aim_and_speed(&map_bytes, &attr)?;
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::Osu.bits());
+    let total = osu.objects.len().max(1) as f64;
+    for count in patterns.counts {
+        result.put_f64(count as f64 / total);
+    }
+    result.put_i32(patterns.longest_stream as i32);
+    result.put_i32(patterns.stream_count as i32);
+    result.put_i32(patterns.burst_count as i32);
+    result.put_f64(aim);
+    result.put_f64(speed);
+    result.put_f64(if aim + speed > 0.0 {
+        aim / (aim + speed)
+    } else {
+        0.0
+    });
+    Ok(result)
+}
+
+struct Patterns {
+    counts: [usize; 6],
+    longest_stream: usize,
+    stream_count: usize,
+    burst_count: usize,
+}
+
+impl Patterns {
+    fn new(osu: &OsuObjects, clock_rate: f64) -> Self {
+        let objects = &osu.objects;
+        let beat_len_at = |time: f64| {
+            osu.map
+                .control_points
+                .timing_point_at(time)
+                .map_or(TimingPoint::DEFAULT_BEAT_LEN, |t| t.beat_len)
+        };
+
+        let mut kinds = vec![OTHER; objects.len()];
+        let mut patterns = Patterns {
+            counts: [0; 6],
+            longest_stream: 0,
+            stream_count: 0,
+            burst_count: 0,
+        };
+
+        // 连续的快速圆圈
+        let mut run_start = 0;
+        for i in 1..=objects.len() {
+            let fast = i < objects.len() && {
+                let (prev, curr) = (&objects[i - 1], &objects[i]);
+                prev.is_circle()
+                    && curr.is_circle()
+                    && curr.start_time - prev.start_time
+                        <= beat_len_at(curr.start_time) / 4.0 * RHYTHM_LENIENCY
+            };
+            if fast {
+                continue;
+            }
+
+            let len = i - run_start;
+            let kind = if len >= MIN_STREAM {
+                patterns.longest_stream = patterns.longest_stream.max(len);
+                patterns.stream_count += 1;
+                Some(STREAM)
+            } else if len >= MIN_BURST {
+                patterns.burst_count += 1;
+                Some(BURST)
+            } else {
+                None
+            };
+            if let Some(kind) = kind {
+                kinds[run_start..i].iter_mut().for_each(|k| *k = kind);
+            }
+            run_start = i;
+        }
+        for (i, h) in objects.iter().enumerate() {
+            if kinds[i] != OTHER {
+                continue;
+            }
+            if h.is_slider() {
+                kinds[i] = SLIDER;
+                continue;
+            }
+            let Some(prev) = i.checked_sub(1).map(|j| &objects[j]) else {
+                continue;
+            };
+            if h.is_spinner() || prev.is_spinner() {
+                continue;
+            }
+
+            let delta = h.start_time - prev.end_time;
+            if delta > beat_len_at(h.start_time) / 2.0 * RHYTHM_LENIENCY {
+                continue;
+            }
+            let dist = prev.stacked_end_pos().distance(h.stacked_pos());
+            if dist >= JUMP_RADII * osu.radius {
+                kinds[i] = JUMP;
+            } else if (h.start_time - prev.start_time) / clock_rate <= ALT_INTERVAL {
+                kinds[i] = ALT;
+            }
+        }
+
+        for kind in kinds {
+            patterns.counts[kind] += 1;
+        }
+        patterns
+    }
+}
+
+/// 与 [`crate::pp::calculate`] 相同的 mods 与倍速处理
+fn aim_and_speed(map_bytes: &[u8], attr: &JniMapAttr) -> Result<(f64, f64)> {
+    let map = Beatmap::from_bytes(map_bytes).map_err(parse_error)?;
+    let difficulty = Difficulty::new().mods(attr.mods);
+    let attributes = if attr.speed > 0.0 {
+        difficulty.clock_rate(attr.speed).calculate(&map)
+    } else {
+        difficulty.calculate(&map)
+    };
+
+    match attributes {
+        DifficultyAttributes::Osu(attributes) => Ok((attributes.aim, attributes.speed)),
+        _ => Ok((0.0, 0.0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 150 bpm: 5 个 burst, 10 个 stream, 两个跳, 一个滑条, 最后是近距离的 1/2
+    fn map() -> String {
+        let mut text = String::from(
+            "osu file format v14
+
+[General]
+Mode: 0
+
+[Difficulty]
+CircleSize:5
+SliderMultiplier:1.4
+
+[TimingPoints]
+0,400,4,2,0,100,1,0
+
+[HitObjects]
+",
+        );
+        let circle = |x: i32, y: i32, time: i32| format!("{x},{y},{time},1,0,0:0:0:0:\n");
+        for i in 0..5 {
+            text.push_str(&circle(100 + i * 10, 100, i * 100));
+        }
+        for i in 0..10 {
+            text.push_str(&circle(100 + i * 10, 300, 1000 + i * 100));
+        }
+        text.push_str(&circle(50, 50, 2500));
+        text.push_str(&circle(450, 300, 2700));
+        text.push_str(&circle(50, 50, 2900));
+        text.push_str("300,100,3500,2,0,L|400:100,1,100\n");
+        text.push_str(&circle(256, 192, 4500));
+        text.push_str(&circle(260, 192, 4700));
+        text
+    }
+
+    fn patterns(text: &str, mods: u32) -> Patterns {
+        let attr = JniMapAttr {
+            mods,
+            ..Default::default()
+        };
+        let osu = get_osu_objects(text.as_bytes(), &attr).unwrap();
+        Patterns::new(&osu, attr.clock_rate())
+    }
+
+    #[test]
+    fn classify() {
+        let patterns = patterns(&map(), 0);
+        assert_eq!(patterns.counts, [10, 5, 2, 0, 1, 3]);
+        assert_eq!(patterns.longest_stream, 10);
+        assert_eq!(patterns.stream_count, 1);
+        assert_eq!(patterns.burst_count, 1);
+    }
+
+    #[test]
+    fn alt_uses_real_interval() {
+        // DT 下 1/2 的实际间隔为 133ms
+        let patterns = patterns(&map(), 1 << 6);
+        assert_eq!(patterns.counts, [10, 5, 2, 1, 1, 2]);
+    }
+
+    #[test]
+    fn bursts_are_not_streams() {
+        // 拆成 8 个与 2 个, 只剩 burst
+        let text = map().replace("180,300,1800,1", "180,300,1850,1");
+        let patterns = patterns(&text, 0);
+        assert_eq!(patterns.longest_stream, 0);
+        assert_eq!(patterns.stream_count, 0);
+        assert_eq!(patterns.burst_count, 2);
+    }
+}
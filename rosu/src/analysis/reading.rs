@@ -0,0 +1,223 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_pp::model::beatmap::BeatmapAttributesBuilder;
+use rosu_pp::model::mode::GameMode;
+use rosu_pp::Beatmap;
+
+use crate::diagnostics::parse_error;
+use crate::encoding::read_map_bytes;
+use crate::java::{Error, Result};
+use crate::objects::{get_objects, KIND_CIRCLE, KIND_SLIDER};
+use crate::pp::{get_map_attr, JniMapAttr};
+use crate::StatusFlag;
+
+/// HD 下物件淡入所占缩圈时间的比例
+const HIDDEN_FADE_IN: f64 = 0.4;
+/// HD 下圆圈在淡入后淡出所占缩圈时间的比例
+const HIDDEN_FADE_OUT: f64 = 0.3;
+
+/// osu! 谱面的读图难度, 统计每个时刻同时可见的物件数 (不计转盘), 时间已按倍速换算
+///
+/// ` [(osu)u8 | (preempt, fade in)f64 * 2 | (hidden)u8 | (peak)i32 | (peak time, average)f64 * 2 | (size)i32 | [(time)f64 | (visible)i32] * size] `
+///
+/// - 物件从 `start - preempt` 开始可见; 无 HD 时圆圈在击打时消失, 滑条在结束时消失
+/// - HD 时圆圈在淡入后淡出, 于 `start - 0.3 * preempt` 完全消失, 滑条在结束时消失
+/// - average 为第一个物件出现到最后一个物件消失之间按时长加权的平均值, 之后为可见数量变化的时间线
+pub fn reading_difficulty(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    attr: &JByteArray,
+) -> Result<Vec<u8>> {
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let attr = get_map_attr(env, attr)?;
+    let Reading {
+        preempt,
+        hidden,
+        timeline,
+        peak,
+        peak_time,
+        average,
+    } = reading(&map_bytes, &attr)?;
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::Osu.bits());
+    result.put_f64(preempt);
+    result.put_f64(preempt * HIDDEN_FADE_IN);
+    result.put_u8(hidden as u8);
+    result.put_i32(peak);
+    result.put_f64(peak_time);
+    result.put_f64(average);
+    result.put_i32(timeline.len() as i32);
+    for (time, count) in timeline {
+        result.put_f64(time);
+        result.put_i32(count);
+    }
+    Ok(result)
+}
+
+struct Reading {
+    preempt: f64,
+    hidden: bool,
+    /// `(time, visible)`
+    timeline: Vec<(f64, i32)>,
+    peak: i32,
+    peak_time: f64,
+    average: f64,
+}
+
+fn reading(map_bytes: &[u8], attr: &JniMapAttr) -> Result<Reading> {
+    let (mode, objects) = get_objects(map_bytes, attr)?;
+    if mode != GameMode::Osu {
+        return Err(Error::from("only osu! beatmap is supported"));
+    }
+
+    let map = Beatmap::from_bytes(map_bytes).map_err(parse_error)?;
+    let preempt = BeatmapAttributesBuilder::new()
+        .map(&map)
+        .mods(attr.mods)
+        .clock_rate(attr.clock_rate())
+        .build()
+        .hit_windows
+        .ar;
+    let hidden = attr.hd();
+    let clock_rate = attr.clock_rate();
+
+    // (时间, 变化量), 同一时刻先处理消失
+    let mut events = Vec::<(f64, i32)>::new();
+    for h in objects
+        .iter()
+        .filter(|h| h.kind == KIND_CIRCLE || h.kind == KIND_SLIDER)
+    {
+        let start = h.start_time / clock_rate;
+        let end = if h.kind == KIND_SLIDER {
+            h.end_time / clock_rate
+        } else if hidden {
+            start - preempt * (1.0 - HIDDEN_FADE_IN - HIDDEN_FADE_OUT)
+        } else {
+            start
+        };
+        events.push((start - preempt, 1));
+        events.push((end, -1));
+    }
+    events.sort_by(|(a, da), (b, db)| a.total_cmp(b).then(da.cmp(db)));
+
+    let mut timeline = Vec::<(f64, i32)>::new();
+    let mut visible = 0;
+    for (time, delta) in events {
+        visible += delta;
+        match timeline.last_mut() {
+            Some(last) if last.0 == time => last.1 = visible,
+            _ => timeline.push((time, visible)),
+        }
+    }
+
+    let (peak_time, peak) =
+        timeline.iter().fold(
+            (0.0, 0),
+            |max, &(time, count)| {
+                if count > max.1 {
+                    (time, count)
+                } else {
+                    max
+                }
+            },
+        );
+    let first_time = timeline.first().map_or(0.0, |(time, _)| *time);
+    let last_time = timeline.last().map_or(0.0, |(time, _)| *time);
+    let weighted: f64 = timeline
+        .windows(2)
+        .map(|w| w[0].1 as f64 * (w[1].0 - w[0].0))
+        .sum();
+    let average = if last_time > first_time {
+        weighted / (last_time - first_time)
+    } else {
+        0.0
+    };
+
+    Ok(Reading {
+        preempt,
+        hidden,
+        timeline,
+        peak,
+        peak_time,
+        average,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// AR5, 缩圈时间为 1200ms
+    const MAP: &str = "osu file format v14
+
+[General]
+Mode: 0
+
+[Difficulty]
+ApproachRate:5
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+100,100,1000,1,0,0:0:0:0:
+200,100,1500,1,0,0:0:0:0:
+300,100,2000,1,0,0:0:0:0:
+256,192,2500,12,0,3000,0:0:0:0:
+";
+
+    fn reading_with(mods: u32) -> Reading {
+        let attr = JniMapAttr {
+            mods,
+            ..Default::default()
+        };
+        reading(MAP.as_bytes(), &attr).unwrap()
+    }
+
+    #[test]
+    fn visible_objects() {
+        let reading = reading_with(0);
+        assert_eq!(reading.preempt, 1200.0);
+        assert_eq!(
+            reading.timeline,
+            vec![
+                (-200.0, 1),
+                (300.0, 2),
+                (800.0, 3),
+                (1000.0, 2),
+                (1500.0, 1),
+                (2000.0, 0)
+            ]
+        );
+        assert_eq!((reading.peak, reading.peak_time), (3, 800.0));
+        assert_eq!(reading.average, 3600.0 / 2200.0);
+    }
+
+    #[test]
+    fn hidden_fades_out_early() {
+        let reading = reading_with(1 << 3);
+        assert!(reading.hidden);
+        assert_eq!(
+            reading.timeline,
+            vec![
+                (-200.0, 1),
+                (300.0, 2),
+                (640.0, 1),
+                (800.0, 2),
+                (1140.0, 1),
+                (1640.0, 0)
+            ]
+        );
+        assert_eq!((reading.peak, reading.peak_time), (2, 300.0));
+    }
+
+    #[test]
+    fn double_time() {
+        let reading = reading_with(1 << 6);
+        assert_eq!(reading.preempt, 800.0);
+        assert_eq!(reading.timeline[0].0, 1000.0 / 1.5 - 800.0);
+        assert_eq!(reading.peak, 3);
+    }
+}
@@ -0,0 +1,184 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::timing_points::{ControlPoints, DifficultyPoint, TimingPoint};
+use rosu_map::Beatmap;
+
+use crate::beatmap::{get_full_map, object_times};
+use crate::java::Result;
+use crate::pp::get_map_attr;
+use crate::timing::most_common_beat_len;
+use crate::StatusFlag;
+
+/// 谱面的滚动速度 (绿线 sv 与 bpm 共同决定) 变化, 时间与 bpm 已按倍速换算
+///
+/// ` [(none)u8 | (base velocity)f64 | (min, max, average)f64 * 3 | (changes)i32 | (size)i32 | [(time, bpm, sv, scroll speed)f64 * 4] * size] `
+///
+/// - base velocity: 最常见 bpm 且 sv 为 1 时的速度 (osu! 像素 / ms), 已包含 slider multiplier
+/// - scroll speed: 相对于 base velocity 的倍数, min / max / average 只统计第一个物件到最后一个物件之间, average 按时长加权
+/// - 时间线从第一个物件开始, 只记录速度实际发生变化的时间点
+pub fn scroll_speed(env: &JNIEnv, local_map: &JByteArray, attr: &JByteArray) -> Result<Vec<u8>> {
+    let mut map = get_full_map(env, local_map)?;
+    let clock_rate = get_map_attr(env, attr)?.clock_rate();
+    let Scroll {
+        base_beat_len,
+        timeline,
+        min,
+        max,
+        average,
+    } = scroll(&mut map);
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::None.bits());
+    result.put_f64(100.0 * map.slider_multiplier / base_beat_len * clock_rate);
+    result.put_f64(min);
+    result.put_f64(max);
+    result.put_f64(average);
+    result.put_i32(timeline.len().saturating_sub(1) as i32);
+    result.put_i32(timeline.len() as i32);
+    for point in timeline {
+        result.put_f64(point.time / clock_rate);
+        result.put_f64(60_000.0 / point.beat_len * clock_rate);
+        result.put_f64(point.sv);
+        result.put_f64(point.speed);
+    }
+    Ok(result)
+}
+
+struct Scroll {
+    base_beat_len: f64,
+    timeline: Vec<ScrollPoint>,
+    min: f64,
+    max: f64,
+    average: f64,
+}
+
+/// 时间线与速度统计, 均为谱面时间
+fn scroll(map: &mut Beatmap) -> Scroll {
+    let times = object_times(map);
+
+    let first_time = times.first().map_or(0.0, |(start, _)| *start);
+    let last_time = times.iter().map(|(_, end)| *end).fold(first_time, f64::max);
+    let control_points = &map.control_points;
+    let base_beat_len = most_common_beat_len(&control_points.timing_points, last_time);
+
+    let mut change_times: Vec<f64> = control_points
+        .timing_points
+        .iter()
+        .map(|t| t.time)
+        .chain(control_points.difficulty_points.iter().map(|d| d.time))
+        .filter(|time| *time > first_time && *time < last_time)
+        .collect();
+    change_times.sort_by(f64::total_cmp);
+
+    let mut timeline = Vec::<ScrollPoint>::new();
+    for time in std::iter::once(first_time).chain(change_times) {
+        let point = ScrollPoint::new(control_points, time, base_beat_len);
+        if timeline.last().is_none_or(|last| last.speed != point.speed) {
+            timeline.push(point);
+        }
+    }
+
+    let mut min = f64::MAX;
+    let mut max = 0.0_f64;
+    let mut weighted = 0.0;
+    for (i, point) in timeline.iter().enumerate() {
+        min = min.min(point.speed);
+        max = max.max(point.speed);
+        let end = timeline.get(i + 1).map_or(last_time, |next| next.time);
+        weighted += point.speed * (end - point.time);
+    }
+    let average = if last_time > first_time {
+        weighted / (last_time - first_time)
+    } else {
+        timeline.first().map_or(0.0, |point| point.speed)
+    };
+
+    Scroll {
+        base_beat_len,
+        min: if timeline.is_empty() { 0.0 } else { min },
+        max,
+        average,
+        timeline,
+    }
+}
+
+struct ScrollPoint {
+    time: f64,
+    beat_len: f64,
+    sv: f64,
+    /// 相对于最常见 bpm 的滚动速度
+    speed: f64,
+}
+
+impl ScrollPoint {
+    fn new(control_points: &ControlPoints, time: f64, base_beat_len: f64) -> Self {
+        let beat_len = control_points
+            .timing_point_at(time)
+            .map_or(TimingPoint::DEFAULT_BEAT_LEN, |t| t.beat_len);
+        let sv = control_points
+            .difficulty_point_at(time)
+            .map_or(DifficultyPoint::DEFAULT_SLIDER_VELOCITY, |d| {
+                d.slider_velocity
+            });
+
+        Self {
+            time,
+            beat_len,
+            sv,
+            speed: sv * base_beat_len / beat_len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 120 bpm 为主, 中间一段 240 bpm, 之后 sv 0.5
+    const MAP: &str = "osu file format v14
+
+[General]
+Mode: 0
+
+[Difficulty]
+SliderMultiplier:1.4
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+4000,250,4,2,0,100,1,0
+5000,500,4,2,0,100,1,0
+7000,-200,4,2,0,100,0,0
+7500,-200,4,2,0,100,0,0
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:
+256,192,9000,1,0,0:0:0:0:
+";
+
+    #[test]
+    fn timeline() {
+        let mut map = Beatmap::from_bytes(MAP.as_bytes()).unwrap();
+        let scroll = scroll(&mut map);
+        assert_eq!(scroll.base_beat_len, 500.0);
+
+        let timeline: Vec<_> = scroll
+            .timeline
+            .iter()
+            .map(|p| (p.time, p.beat_len, p.sv, p.speed))
+            .collect();
+        // 7500 的绿线没有改变速度
+        assert_eq!(
+            timeline,
+            vec![
+                (1000.0, 500.0, 1.0, 1.0),
+                (4000.0, 250.0, 1.0, 2.0),
+                (5000.0, 500.0, 1.0, 1.0),
+                (7000.0, 500.0, 0.5, 0.5),
+            ]
+        );
+        assert_eq!(scroll.min, 0.5);
+        assert_eq!(scroll.max, 2.0);
+        assert_eq!(scroll.average, (3000.0 + 2000.0 + 2000.0 + 1000.0) / 8000.0);
+    }
+}
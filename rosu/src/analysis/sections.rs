@@ -0,0 +1,281 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_pp::any::Strains;
+use rosu_pp::{Beatmap, Difficulty};
+
+use crate::java::{Error, Result};
+use crate::pp::{get_map_and_attr, JniMapAttr};
+use crate::{mode_flag, vec_add_str};
+
+/// 由 strain 找出最难的若干段以及难度突增的区间, 时间为实际时间 (已按倍速换算)
+///
+/// ` [(mode)u8 | (section length, average strain)f64 * 2 | (skill size)i32 | (skill name)str * skill size | (top size)i32 | section * top size | (spike size)i32 | section * spike size] `
+/// - section: `[(start, end, strain)f64 * 3 | (contribution)f64 * skill size]`
+///
+/// 每个 strain 区间的强度为各项 skill 之和, contribution 为各项 skill 在其中的占比;
+/// 最难的段为长度 `window` 内平均强度最高且互不重叠的 `top` 段,
+/// 突增为强度连续超过平均值 (不计强度为 0 的区间) `spike_factor` 倍的区间, 其 strain 为峰值
+pub fn hard_sections(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    attr: &JByteArray,
+    window: f64,
+    top: i32,
+    spike_factor: f64,
+) -> Result<Vec<u8>> {
+    if !window.is_finite() || window <= 0.0 {
+        return Err(Error::from("window must be positive"));
+    }
+    if !spike_factor.is_finite() || spike_factor <= 0.0 {
+        return Err(Error::from("spike factor must be positive"));
+    }
+
+    let (map, attr) = get_map_and_attr(env, local_map, attr)?;
+    let Sections {
+        section_len,
+        average,
+        skills,
+        top,
+        spikes,
+    } = sections(&map, &attr, window, top, spike_factor);
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(mode_flag(map.mode).bits());
+    result.put_f64(section_len);
+    result.put_f64(average);
+    result.put_i32(skills.len() as i32);
+    for name in &skills {
+        vec_add_str(name, &mut result);
+    }
+    for sections in [top, spikes] {
+        result.put_i32(sections.len() as i32);
+        for section in sections {
+            result.put_f64(section.start_time);
+            result.put_f64(section.end_time);
+            result.put_f64(section.strain);
+            for c in section.contribution {
+                result.put_f64(c);
+            }
+        }
+    }
+    Ok(result)
+}
+
+struct Sections {
+    section_len: f64,
+    average: f64,
+    skills: Vec<&'static str>,
+    top: Vec<Section>,
+    spikes: Vec<Section>,
+}
+
+struct Section {
+    start_time: f64,
+    end_time: f64,
+    strain: f64,
+    contribution: Vec<f64>,
+}
+
+/// 由 strain 计算最难的段与突增, 时间已按倍速换算
+fn sections(
+    map: &Beatmap,
+    attr: &JniMapAttr,
+    window: f64,
+    top: i32,
+    spike_factor: f64,
+) -> Sections {
+    let difficulty = Difficulty::new().mods(attr.mods);
+    let strains = if attr.speed > 0.0 {
+        difficulty.clock_rate(attr.speed).strains(map)
+    } else {
+        difficulty.strains(map)
+    };
+
+    let section_len = strains.section_len();
+    let skills: Vec<(&str, Vec<f64>)> = match strains {
+        Strains::Osu(s) => {
+            let mut skills = vec![("aim", s.aim), ("speed", s.speed)];
+            if attr.fl() {
+                skills.push(("flashlight", s.flashlight));
+            }
+            skills
+        }
+        Strains::Taiko(s) => vec![
+            ("color", s.color),
+            ("rhythm", s.rhythm),
+            ("stamina", s.stamina),
+        ],
+        Strains::Catch(s) => vec![("movement", s.movement)],
+        Strains::Mania(s) => vec![("strain", s.strains)],
+    };
+    let len = skills.iter().map(|(_, s)| s.len()).max().unwrap_or(0);
+    let totals: Vec<f64> = (0..len)
+        .map(|i| skills.iter().filter_map(|(_, s)| s.get(i)).sum())
+        .collect();
+
+    // 第一个 strain 区间在第二个物件所在区间结束
+    let clock_rate = attr.clock_rate();
+    let first_time = map
+        .hit_objects
+        .get(1)
+        .map_or(0.0, |h| h.start_time / clock_rate);
+    let offset = (first_time / section_len).ceil() * section_len - section_len;
+    let range = |start: usize, end: usize| {
+        (
+            offset + start as f64 * section_len,
+            offset + end as f64 * section_len,
+        )
+    };
+
+    let contribution = |start: usize, end: usize| -> Vec<f64> {
+        let total: f64 = totals[start..end].iter().sum();
+        skills
+            .iter()
+            .map(|(_, s)| {
+                let skill: f64 = s[start.min(s.len())..end.min(s.len())].iter().sum();
+                if total > 0.0 {
+                    skill / total
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    };
+
+    let width = ((window / section_len).round() as usize).clamp(1, len.max(1));
+    let top_sections = top_windows(&totals, width, top.max(0) as usize);
+    let (average, spike_sections) = spikes(&totals, spike_factor);
+
+    let section = |(start, end, strain): (usize, usize, f64)| {
+        let (start_time, end_time) = range(start, end);
+        Section {
+            start_time,
+            end_time,
+            strain,
+            contribution: contribution(start, end),
+        }
+    };
+
+    Sections {
+        section_len,
+        average,
+        skills: skills.iter().map(|(name, _)| *name).collect(),
+        top: top_sections.into_iter().map(section).collect(),
+        spikes: spike_sections.into_iter().map(section).collect(),
+    }
+}
+
+/// 滑动窗口的平均强度, 贪心选出互不重叠的最高 `top` 段, 返回 `(start, end, strain)` 区间下标
+fn top_windows(totals: &[f64], width: usize, top: usize) -> Vec<(usize, usize, f64)> {
+    let mut windows: Vec<(usize, f64)> = totals
+        .windows(width)
+        .enumerate()
+        .map(|(i, w)| (i, w.iter().sum::<f64>() / width as f64))
+        .collect();
+    windows.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    let mut top_sections = Vec::<(usize, usize, f64)>::new();
+    for (start, strain) in windows {
+        if top_sections.len() >= top {
+            break;
+        }
+        let end = start + width;
+        if top_sections
+            .iter()
+            .all(|(s, e, _)| end <= *s || start >= *e)
+        {
+            top_sections.push((start, end, strain));
+        }
+    }
+    top_sections
+}
+
+/// 不计强度为 0 的区间的平均强度, 以及连续超过其 `spike_factor` 倍的区间 (strain 为峰值)
+fn spikes(totals: &[f64], spike_factor: f64) -> (f64, Vec<(usize, usize, f64)>) {
+    let non_zero: Vec<f64> = totals.iter().copied().filter(|s| *s > 0.0).collect();
+    let average = if non_zero.is_empty() {
+        0.0
+    } else {
+        non_zero.iter().sum::<f64>() / non_zero.len() as f64
+    };
+    let threshold = average * spike_factor;
+    let mut spikes = Vec::<(usize, usize, f64)>::new();
+    let mut i = 0;
+    while i < totals.len() {
+        // 用 > 判断, strain 为 NaN 时也会前进
+        if totals[i] > threshold {
+            let start = i;
+            let mut peak = 0.0_f64;
+            while i < totals.len() && totals[i] > threshold {
+                peak = peak.max(totals[i]);
+                i += 1;
+            }
+            spikes.push((start, i, peak));
+        } else {
+            i += 1;
+        }
+    }
+    (average, spikes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 前后各 8 秒每 500ms 一个物件, 中间 4 秒是间隔 100ms 的来回跳
+    fn map() -> Beatmap {
+        let mut text = String::from(
+            "osu file format v14\n\n[General]\nMode: 0\n\n[TimingPoints]\n0,400,4,2,0,100,1,0\n\n[HitObjects]\n",
+        );
+        let slow = |start: i32| (0..16).map(move |i| (256, 192, start + i * 500));
+        let fast = (0..40).map(|i| (if i % 2 == 0 { 100 } else { 400 }, 192, 8000 + i * 100));
+        for (x, y, time) in slow(0).chain(fast).chain(slow(12000)) {
+            text.push_str(&format!("{x},{y},{time},1,0,0:0:0:0:\n"));
+        }
+        Beatmap::from_bytes(text.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn hardest_section_and_spike() {
+        let map = map();
+        let sections = sections(&map, &JniMapAttr::default(), 2000.0, 1, 1.5);
+        assert_eq!(sections.section_len, 400.0);
+        assert_eq!(sections.skills, vec!["aim", "speed"]);
+
+        // 最难的段在跳的后半部分, 强度在跳开始后逐渐上升
+        let top = &sections.top[0];
+        assert_eq!((top.start_time, top.end_time), (10000.0, 12000.0));
+        assert!((top.contribution.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(top.contribution[0] > top.contribution[1]);
+
+        // 突增从跳开始, 在跳结束后的衰减中结束
+        assert_eq!(sections.spikes.len(), 1);
+        let spike = &sections.spikes[0];
+        assert_eq!(spike.start_time, 8000.0);
+        assert!(spike.end_time > 12000.0 && spike.end_time < 14000.0);
+        assert!(spike.strain > sections.average * 1.5);
+        assert!(spike.strain >= top.strain);
+    }
+
+    #[test]
+    fn top_windows_do_not_overlap() {
+        let totals = [1.0, 5.0, 6.0, 2.0, 0.0, 4.0, 4.0, 1.0];
+        assert_eq!(
+            top_windows(&totals, 2, 3),
+            vec![(1, 3, 5.5), (5, 7, 4.0), (3, 5, 1.0)]
+        );
+        assert_eq!(top_windows(&totals, 2, 0), Vec::new());
+    }
+
+    #[test]
+    fn spikes_above_average() {
+        // 平均值不计 0
+        let totals = [0.0, 2.0, 7.0, 1.0, 0.0, 8.0];
+        assert_eq!(spikes(&totals, 2.0), (4.5, Vec::new()));
+        assert_eq!(spikes(&totals, 1.5), (4.5, vec![(2, 3, 7.0), (5, 6, 8.0)]));
+
+        let (average, spikes) = spikes(&[1.0, f64::NAN, 9.0, 1.0], 2.0);
+        assert_eq!(average, 11.0 / 3.0);
+        assert_eq!(spikes, vec![(2, 3, 9.0)]);
+    }
+}
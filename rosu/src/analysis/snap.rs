@@ -0,0 +1,166 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::timing_points::{ControlPoints, TimingPoint};
+use rosu_map::Beatmap;
+
+use crate::beatmap::get_full_map;
+use crate::java::{Error, Result};
+use crate::StatusFlag;
+
+/// 检查的分音, 按顺序取第一个吻合的
+pub(crate) const SNAP_DIVISORS: [u32; 6] = [1, 2, 4, 8, 3, 6];
+/// 与分音线相差不超过该值 (ms) 视为在线上, 谱面中的时间为整数毫秒
+const SNAP_TOLERANCE: f64 = 2.0;
+
+/// 物件所在的分音, 未对齐时为离最近分音线的偏移 (ms, 正数表示偏后)
+enum Snap {
+    Snapped(u32),
+    Unsnapped(f64),
+}
+
+/// 相对于所在红线判断 `time` 的分音, `divisors` 按顺序取第一个吻合的
+fn snap_time(control_points: &ControlPoints, time: f64, divisors: &[u32]) -> Option<Snap> {
+    let timing_point = control_points.timing_point_at(time)?;
+    let mut nearest = f64::MAX;
+
+    for &divisor in divisors {
+        let offset = tick_offset(timing_point, time, divisor);
+
+        if offset.abs() <= SNAP_TOLERANCE {
+            return Some(Snap::Snapped(divisor));
+        }
+        if offset.abs() < nearest.abs() {
+            nearest = offset;
+        }
+    }
+
+    Some(Snap::Unsnapped(nearest))
+}
+
+/// 相对于所在红线离 `time` 最近的分音线, 返回其分音与偏移 (ms, 正数表示偏后);
+/// 多个分音的线重合时取 `divisors` 中靠前的
+pub(crate) fn nearest_snap(
+    control_points: &ControlPoints,
+    time: f64,
+    divisors: &[u32],
+) -> Option<(u32, f64)> {
+    let timing_point = control_points.timing_point_at(time)?;
+    let mut nearest: Option<(u32, f64)> = None;
+
+    for &divisor in divisors {
+        let offset = tick_offset(timing_point, time, divisor);
+        // 重合的分音线因浮点误差可能略有不同
+        match nearest {
+            Some((_, nearest)) if offset.abs() >= nearest.abs() - 1e-6 => {}
+            _ => nearest = Some((divisor, offset)),
+        }
+    }
+
+    nearest
+}
+
+/// 离 `time` 最近的 1/`divisor` 分音线的偏移
+fn tick_offset(timing_point: &TimingPoint, time: f64, divisor: u32) -> f64 {
+    let step = timing_point.beat_len / divisor as f64;
+    let beats = ((time - timing_point.time) / step).round();
+    time - (timing_point.time + beats * step)
+}
+
+/// 统计物件开始时间所在的分音, 相对于所在红线, 时间为谱面时间
+///
+/// ` [(none)u8 | (total)i32 | (size)i32 | [(divisor, count)i32 * 2] * size | (unsnapped size)i32 | [(index)i32 | (time, offset)f64 * 2] * unsnapped size] `
+///
+/// 分音依次为 1/1, 1/2, 1/4, 1/8, 1/3, 1/6, 同时符合多个时取较粗的一个
+pub fn rhythm_snap(env: &JNIEnv, local_map: &JByteArray) -> Result<Vec<u8>> {
+    let map = get_full_map(env, local_map)?;
+    if map.control_points.timing_points.is_empty() {
+        return Err(Error::from("beatmap has no timing points"));
+    }
+
+    let (counts, unsnapped) = snap_counts(&map);
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::None.bits());
+    result.put_i32(map.hit_objects.len() as i32);
+    result.put_i32(SNAP_DIVISORS.len() as i32);
+    for (divisor, count) in SNAP_DIVISORS.iter().zip(counts) {
+        result.put_i32(*divisor as i32);
+        result.put_i32(count);
+    }
+    result.put_i32(unsnapped.len() as i32);
+    for (i, time, offset) in unsnapped {
+        result.put_i32(i as i32);
+        result.put_f64(time);
+        result.put_f64(offset);
+    }
+    Ok(result)
+}
+
+/// 各分音的物件数, 以及未对齐的 `(index, time, offset)`
+fn snap_counts(map: &Beatmap) -> ([i32; SNAP_DIVISORS.len()], Vec<(usize, f64, f64)>) {
+    let mut counts = [0; SNAP_DIVISORS.len()];
+    let mut unsnapped = Vec::new();
+    for (i, h) in map.hit_objects.iter().enumerate() {
+        match snap_time(&map.control_points, h.start_time, &SNAP_DIVISORS) {
+            Some(Snap::Snapped(divisor)) => {
+                let idx = SNAP_DIVISORS.iter().position(|d| *d == divisor).unwrap();
+                counts[idx] += 1;
+            }
+            Some(Snap::Unsnapped(offset)) => unsnapped.push((i, h.start_time, offset)),
+            None => {}
+        }
+    }
+    (counts, unsnapped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 200 bpm, 红线从 100ms 开始
+    fn map(times: &[i32]) -> Beatmap {
+        let mut text = String::from(
+            "osu file format v14\n\n[General]\nMode: 0\n\n[TimingPoints]\n100,300,4,2,0,100,1,0\n\n[HitObjects]\n",
+        );
+        for time in times {
+            text.push_str(&format!("256,192,{time},1,0,0:0:0:0:\n"));
+        }
+        Beatmap::from_bytes(text.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn divisor_histogram() {
+        // 1/1, 1/2, 1/4, 1/8 (137.5 取整), 1/3, 1/6, 1/1
+        let map = map(&[100, 250, 175, 138, 200, 150, 400, 1300]);
+        let (counts, unsnapped) = snap_counts(&map);
+        assert_eq!(counts, [3, 1, 1, 1, 1, 1]);
+        assert!(unsnapped.is_empty());
+    }
+
+    #[test]
+    fn unsnapped_offset() {
+        let map = map(&[100, 395, 410]);
+        let (counts, unsnapped) = snap_counts(&map);
+        assert_eq!(counts, [1, 0, 0, 0, 0, 0]);
+        assert_eq!(unsnapped, vec![(1, 395.0, -5.0), (2, 410.0, 10.0)]);
+    }
+
+    #[test]
+    fn nearest_divisor() {
+        let map = map(&[]);
+        let points = &map.control_points;
+        let divisors = [1, 2, 3, 4, 6, 8, 12, 16];
+
+        // 1/2 的线同时也是 1/4, 1/6 等的线
+        assert_eq!(nearest_snap(points, 250.0, &divisors), Some((2, 0.0)));
+        // 1/3 的线 (200) 同时也是 1/12 的线
+        let (divisor, offset) = nearest_snap(points, 198.0, &divisors).unwrap();
+        assert_eq!(divisor, 3);
+        assert!((offset - -2.0).abs() < 1e-9);
+        // 离 1/3 的线 9ms, 离 1/16 的线 (193.75) 更近
+        let (divisor, offset) = nearest_snap(points, 191.0, &divisors).unwrap();
+        assert_eq!(divisor, 16);
+        assert!((offset - -2.75).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,240 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+
+use crate::encoding::read_map_bytes;
+use crate::java::Result;
+use crate::osu::{get_osu_objects, OsuObject};
+use crate::pp::get_map_attr;
+use crate::StatusFlag;
+
+/// 输出的百分位
+const PERCENTILES: [f64; 6] = [25.0, 50.0, 75.0, 90.0, 95.0, 99.0];
+/// 间距 (osu! 像素) 直方图的区间宽度
+const DISTANCE_BIN: f64 = 32.0;
+/// 间距 (半径) 直方图的区间宽度
+const RADII_BIN: f64 = 0.5;
+/// 角度直方图的区间宽度, 范围 0 到 180 度
+const ANGLE_BIN: f64 = 10.0;
+/// 速度 (osu! 像素 / ms) 直方图的区间宽度
+const VELOCITY_BIN: f64 = 0.25;
+/// 计算速度所需的最短间隔 (ms), 滑条尾的时间不是整数, 间隔过短时速度没有意义
+const MIN_VELOCITY_DELTA: f64 = 1.0;
+/// 直方图的最多区间数, 超出的值计入最后一个区间
+const MAX_BINS: usize = 1024;
+
+/// osu! 谱面相邻物件的间距, 角度与速度统计, mods 影响 HR 翻转与圆圈大小, 速度按倍速换算
+///
+/// ` [(osu)u8 | (radius)f32 | distance | distance in radii | angle | velocity] `
+///
+/// 每项分布为 ` [(average, max)f64 * 2 | (p25, p50, p75, p90, p95, p99)f64 * 6 | (bin width)f64 | (size)i32 | (count)i32 * size] `
+///
+/// - 间距为前一物件的结尾 (滑条尾) 到当前物件的距离, 转盘前后不计
+/// - 角度为连续三个物件在中间物件处的夹角, 180 度为直线
+/// - 速度只统计间隔不短于 1ms 的物件, 直方图最多 1024 个区间, 最后一个区间包含更大的值
+pub fn spacing_statistics(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    attr: &JByteArray,
+) -> Result<Vec<u8>> {
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let attr = get_map_attr(env, attr)?;
+    let osu = get_osu_objects(&map_bytes, &attr)?;
+    let radius = osu.radius as f64;
+    let Spacing {
+        distances,
+        angles,
+        velocities,
+    } = spacing(&osu.objects, attr.clock_rate());
+    let radii: Vec<f64> = distances.iter().map(|d| d / radius).collect();
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::Osu.bits());
+    result.put_f32(osu.radius);
+    distribution_to_bytes(distances, DISTANCE_BIN, &mut result);
+    distribution_to_bytes(radii, RADII_BIN, &mut result);
+    distribution_to_bytes(angles, ANGLE_BIN, &mut result);
+    distribution_to_bytes(velocities, VELOCITY_BIN, &mut result);
+    Ok(result)
+}
+
+struct Spacing {
+    distances: Vec<f64>,
+    angles: Vec<f64>,
+    velocities: Vec<f64>,
+}
+
+/// 相邻物件的间距与速度, 以及连续三个物件的夹角
+fn spacing(objects: &[OsuObject], clock_rate: f64) -> Spacing {
+    let mut distances = Vec::new();
+    let mut velocities = Vec::new();
+    let mut angles = Vec::new();
+    for i in 1..objects.len() {
+        let (prev, curr) = (&objects[i - 1], &objects[i]);
+        if prev.is_spinner() || curr.is_spinner() {
+            continue;
+        }
+
+        let distance = prev.stacked_end_pos().distance(curr.stacked_pos()) as f64;
+        distances.push(distance);
+        let delta = (curr.start_time - prev.end_time) / clock_rate;
+        if delta >= MIN_VELOCITY_DELTA {
+            velocities.push(distance / delta);
+        }
+
+        if let Some(angle) = i
+            .checked_sub(2)
+            .and_then(|j| angle(&objects[j], prev, curr))
+        {
+            angles.push(angle);
+        }
+    }
+    Spacing {
+        distances,
+        angles,
+        velocities,
+    }
+}
+
+/// 中间物件处的夹角 (度), 任一段长度过短或经过转盘时没有意义
+fn angle(prev: &OsuObject, curr: &OsuObject, next: &OsuObject) -> Option<f64> {
+    if prev.is_spinner() || curr.is_spinner() || next.is_spinner() {
+        return None;
+    }
+
+    let v1 = prev.stacked_end_pos() - curr.stacked_pos();
+    let v2 = next.stacked_pos() - curr.stacked_end_pos();
+    if v1.length() < 1.0 || v2.length() < 1.0 {
+        return None;
+    }
+
+    let dot = v1.dot(v2) as f64 / (v1.length() as f64 * v2.length() as f64);
+    Some(dot.clamp(-1.0, 1.0).acos().to_degrees())
+}
+
+fn distribution_to_bytes(mut values: Vec<f64>, bin_width: f64, result: &mut Vec<u8>) {
+    values.retain(|v| v.is_finite());
+    values.sort_by(f64::total_cmp);
+    let max = values.last().copied().unwrap_or(0.0);
+    let average = if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    };
+
+    result.put_f64(average);
+    result.put_f64(max);
+    for p in PERCENTILES {
+        // nearest-rank
+        let rank = (p / 100.0 * values.len() as f64).ceil() as usize;
+        result.put_f64(values.get(rank.saturating_sub(1)).copied().unwrap_or(0.0));
+    }
+
+    let mut histogram = if values.is_empty() {
+        Vec::new()
+    } else {
+        vec![0; ((max / bin_width) as usize + 1).min(MAX_BINS)]
+    };
+    let last_bin = histogram.len().saturating_sub(1);
+    for value in &values {
+        histogram[((value / bin_width) as usize).min(last_bin)] += 1;
+    }
+
+    result.put_f64(bin_width);
+    result.put_i32(histogram.len() as i32);
+    for count in histogram {
+        result.put_i32(count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Buf;
+
+    use super::*;
+    use crate::pp::JniMapAttr;
+
+    /// 边长 100 的正方形, 之后经过转盘
+    const MAP: &str = "osu file format v14
+
+[General]
+Mode: 0
+
+[Difficulty]
+CircleSize:4
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+100,100,0,1,0,0:0:0:0:
+200,100,500,1,0,0:0:0:0:
+200,200,1000,1,0,0:0:0:0:
+100,200,1500,1,0,0:0:0:0:
+100,100,1750,1,0,0:0:0:0:
+256,192,2000,12,0,3000,0:0:0:0:
+400,300,3500,1,0,0:0:0:0:
+";
+
+    fn spacing_with(mods: u32) -> (f32, Spacing) {
+        let attr = JniMapAttr {
+            mods,
+            ..Default::default()
+        };
+        let osu = get_osu_objects(MAP.as_bytes(), &attr).unwrap();
+        (osu.radius, spacing(&osu.objects, attr.clock_rate()))
+    }
+
+    #[test]
+    fn square() {
+        let (
+            radius,
+            Spacing {
+                distances,
+                angles,
+                velocities,
+            },
+        ) = spacing_with(0);
+        assert!((radius - 36.48).abs() < 1e-4);
+        assert_eq!(distances, vec![100.0; 4]);
+        assert_eq!(velocities, vec![0.2, 0.2, 0.2, 0.4]);
+        assert_eq!(angles.len(), 3);
+        assert!(angles.iter().all(|a| (a - 90.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn velocity_with_clock_rate() {
+        let (_, Spacing { velocities, .. }) = spacing_with(1 << 6);
+        let expected = [0.3, 0.3, 0.3, 0.6];
+        assert_eq!(velocities.len(), expected.len());
+        assert!(velocities
+            .iter()
+            .zip(expected)
+            .all(|(v, e)| (v - e).abs() < 1e-9));
+    }
+
+    #[test]
+    fn distribution() {
+        let mut values: Vec<f64> = (1..=100).map(f64::from).collect();
+        values.push(f64::NAN);
+        values.push(1e9);
+        let mut bytes = Vec::new();
+        distribution_to_bytes(values, 10.0, &mut bytes);
+
+        let mut buf = bytes.as_slice();
+        let average = buf.get_f64();
+        assert!((average - (5050.0 + 1e9) / 101.0).abs() < 1e-6);
+        assert_eq!(buf.get_f64(), 1e9);
+        let percentiles: Vec<f64> = (0..PERCENTILES.len()).map(|_| buf.get_f64()).collect();
+        assert_eq!(percentiles, vec![26.0, 51.0, 76.0, 91.0, 96.0, 100.0]);
+        assert_eq!(buf.get_f64(), 10.0);
+        assert_eq!(buf.get_i32() as usize, MAX_BINS);
+        let histogram: Vec<i32> = (0..MAX_BINS).map(|_| buf.get_i32()).collect();
+        assert_eq!(histogram[0], 9);
+        assert_eq!(histogram[1], 10);
+        assert_eq!(histogram[10], 1);
+        assert_eq!(histogram[MAX_BINS - 1], 1);
+        assert_eq!(histogram.iter().sum::<i32>(), 101);
+        assert!(buf.is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::hit_objects::hit_samples::HitSoundType;
+use rosu_pp::model::mode::GameMode;
+
+use crate::encoding::read_map_bytes;
+use crate::java::{Error, Result};
+use crate::objects::{get_objects, ObjectInfo, KIND_CIRCLE, KIND_SLIDER, KIND_SPINNER};
+use crate::pp::get_map_attr;
+use crate::StatusFlag;
+
+/// taiko 谱面的颜色与节奏统计, 支持转谱, 时间已按倍速换算
+///
+/// ` [(taiko)u8 | (don, kat, finisher)i32 * 3 | (don ratio)f64 | (longest mono)i32 | (roll count)i32 | (roll duration)f64 | (swell count)i32 | (swell duration)f64 | (colour changes)i32 | (change ratio, changes per second)f64 * 2] `
+///
+/// - 带 whistle 或 clap 的音符为 kat, 带 finish 的为大音符
+/// - 单色连续与颜色变化只在相邻的音符之间计算, 中间有鼓棒或转盘时断开
+/// - change ratio 为颜色变化次数与相邻音符对数之比
+pub fn taiko_statistics(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    attr: &JByteArray,
+) -> Result<Vec<u8>> {
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let attr = get_map_attr(env, attr)?;
+    let (mode, objects) = get_objects(&map_bytes, &attr)?;
+    if mode != GameMode::Taiko {
+        return Err(Error::from("only taiko beatmap is supported"));
+    }
+    let Taiko {
+        don,
+        kat,
+        finisher,
+        rolls,
+        roll_duration,
+        swells,
+        swell_duration,
+        longest_mono,
+        changes,
+        pairs,
+        duration,
+    } = taiko(&objects, attr.clock_rate());
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::Taiko.bits());
+    result.put_i32(don);
+    result.put_i32(kat);
+    result.put_i32(finisher);
+    result.put_f64(if don + kat > 0 {
+        don as f64 / (don + kat) as f64
+    } else {
+        0.0
+    });
+    result.put_i32(longest_mono);
+    result.put_i32(rolls);
+    result.put_f64(roll_duration);
+    result.put_i32(swells);
+    result.put_f64(swell_duration);
+    result.put_i32(changes);
+    result.put_f64(if pairs > 0 {
+        changes as f64 / pairs as f64
+    } else {
+        0.0
+    });
+    result.put_f64(if duration > 0.0 {
+        changes as f64 / duration
+    } else {
+        0.0
+    });
+    Ok(result)
+}
+
+struct Taiko {
+    don: i32,
+    kat: i32,
+    finisher: i32,
+    rolls: i32,
+    roll_duration: f64,
+    swells: i32,
+    swell_duration: f64,
+    longest_mono: i32,
+    changes: i32,
+    /// 相邻音符的对数
+    pairs: i32,
+    /// 第一个到最后一个物件的时长 (s)
+    duration: f64,
+}
+
+fn taiko(objects: &[ObjectInfo], clock_rate: f64) -> Taiko {
+    let mut don = 0;
+    let mut kat = 0;
+    let mut finisher = 0;
+    let mut rolls = 0;
+    let mut roll_duration = 0.0;
+    let mut swells = 0;
+    let mut swell_duration = 0.0;
+    let mut longest_mono = 0;
+    let mut changes = 0;
+    let mut pairs = 0;
+
+    // 上一个音符是否为 kat, 以及当前单色连续的长度
+    let mut prev: Option<bool> = None;
+    let mut mono = 0;
+    for h in objects {
+        match h.kind {
+            KIND_CIRCLE => {
+                let is_kat = h.hit_sound & (HitSoundType::WHISTLE | HitSoundType::CLAP) != 0;
+                if is_kat {
+                    kat += 1;
+                } else {
+                    don += 1;
+                }
+                if h.hit_sound & HitSoundType::FINISH != 0 {
+                    finisher += 1;
+                }
+
+                match prev {
+                    Some(prev_kat) => {
+                        pairs += 1;
+                        if prev_kat == is_kat {
+                            mono += 1;
+                        } else {
+                            changes += 1;
+                            mono = 1;
+                        }
+                    }
+                    None => mono = 1,
+                }
+                longest_mono = longest_mono.max(mono);
+                prev = Some(is_kat);
+            }
+            KIND_SLIDER => {
+                rolls += 1;
+                roll_duration += (h.end_time - h.start_time) / clock_rate;
+                prev = None;
+            }
+            KIND_SPINNER => {
+                swells += 1;
+                swell_duration += (h.end_time - h.start_time) / clock_rate;
+                prev = None;
+            }
+            _ => prev = None,
+        }
+    }
+
+    let duration = match (objects.first(), objects.last()) {
+        (Some(first), Some(last)) => (last.start_time - first.start_time) / clock_rate / 1000.0,
+        _ => 0.0,
+    };
+
+    Taiko {
+        don,
+        kat,
+        finisher,
+        rolls,
+        roll_duration,
+        swells,
+        swell_duration,
+        longest_mono,
+        changes,
+        pairs,
+        duration,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pp::JniMapAttr;
+
+    /// whistle (2) 与 clap (8) 为 kat, finish (4) 为大音符
+    const MAP: &str = "osu file format v14
+
+[General]
+Mode: 1
+
+[Difficulty]
+SliderMultiplier:1.4
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+256,192,0,1,0,0:0:0:0:
+256,192,100,1,0,0:0:0:0:
+256,192,200,1,4,0:0:0:0:
+256,192,300,1,2,0:0:0:0:
+256,192,400,1,8,0:0:0:0:
+256,192,500,2,0,L|356:192,1,100
+256,192,2000,1,2,0:0:0:0:
+256,192,2100,1,0,0:0:0:0:
+256,192,2500,12,0,3500,0:0:0:0:
+256,192,4000,1,0,0:0:0:0:
+";
+
+    #[test]
+    fn colours_and_rolls() {
+        let (_, objects) = get_objects(MAP.as_bytes(), &JniMapAttr::default()).unwrap();
+        let taiko = taiko(&objects, 1.0);
+        assert_eq!((taiko.don, taiko.kat, taiko.finisher), (5, 3, 1));
+        // 鼓棒与转盘打断单色连续
+        assert_eq!(taiko.longest_mono, 3);
+        assert_eq!((taiko.changes, taiko.pairs), (2, 5));
+        assert_eq!(taiko.rolls, 1);
+        assert!((taiko.roll_duration - 100.0 / 140.0 * 500.0).abs() < 1e-9);
+        assert_eq!((taiko.swells, taiko.swell_duration), (1, 1000.0));
+        assert_eq!(taiko.duration, 4.0);
+
+        let taiko = self::taiko(&objects, 2.0);
+        assert_eq!((taiko.swell_duration, taiko.duration), (500.0, 2.0));
+    }
+}
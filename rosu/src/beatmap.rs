@@ -0,0 +1,54 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::hit_objects::CurveBuffers;
+
+use crate::diagnostics::parse_error;
+use crate::encoding::read_map_bytes;
+use crate::java::Result;
+use crate::{vec_add_str, StatusFlag};
+
+/// 读取谱面的元数据
+///
+/// ` [(none)u8 | title | title unicode | artist | artist unicode | creator | version | source | tags | (beatmap id)i32 | (beatmap set id)i32 | audio file | (preview time)i32 | background file] `
+///
+/// 其中字符串均为 `[(length)i32 | (utf-8)u8 * length]`
+pub fn parse_metadata(env: &JNIEnv, local_map: &JByteArray) -> Result<Vec<u8>> {
+    let map = get_full_map(env, local_map)?;
+    let mut result = Vec::<u8>::new();
+    metadata_to_bytes(&map, &mut result);
+    Ok(result)
+}
+
+/// 从 java byte[] 读取完整的谱面, 与 [`rosu_pp::Beatmap`] 不同, 会保留元数据以及事件等信息
+pub(crate) fn get_full_map(env: &JNIEnv, local_map: &JByteArray) -> Result<rosu_map::Beatmap> {
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let map = rosu_map::Beatmap::from_bytes(&map_bytes).map_err(parse_error)?;
+    Ok(map)
+}
+
+/// 每个物件的开始与结束时间, 滑条的结束时间需要计算曲线长度
+pub(crate) fn object_times(map: &mut rosu_map::Beatmap) -> Vec<(f64, f64)> {
+    let mut bufs = CurveBuffers::default();
+    map.hit_objects
+        .iter_mut()
+        .map(|h| (h.start_time, h.end_time_with_bufs(&mut bufs)))
+        .collect()
+}
+
+fn metadata_to_bytes(map: &rosu_map::Beatmap, result: &mut dyn BufMut) {
+    result.put_u8(StatusFlag::None.bits());
+    vec_add_str(&map.title, result);
+    vec_add_str(&map.title_unicode, result);
+    vec_add_str(&map.artist, result);
+    vec_add_str(&map.artist_unicode, result);
+    vec_add_str(&map.creator, result);
+    vec_add_str(&map.version, result);
+    vec_add_str(&map.source, result);
+    vec_add_str(&map.tags, result);
+    result.put_i32(map.beatmap_id);
+    result.put_i32(map.beatmap_set_id);
+    vec_add_str(&map.audio_file, result);
+    result.put_i32(map.preview_time);
+    vec_add_str(&map.background_file, result);
+}
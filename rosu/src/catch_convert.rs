@@ -0,0 +1,649 @@
+//! rosu-pp 的 catch 转谱不公开, 这里照搬 rosu-pp =1.1.0 的实现:
+//! - `src/catch/convert.rs`: 转谱, HR 偏移与 hyperdash
+//! - `src/catch/object/juice_stream.rs`, `banana_shower.rs`: 滑条与转盘的拆分
+//! - `src/catch/catcher.rs`: 接盘宽度
+//! - `src/util/random.rs`: osu!stable 的随机数
+//! - `src/util/sort/csharp.rs`, `src/util/sort/mod.rs`: C# 的不稳定排序
+//!
+//! 升级 rosu-pp 时需要对照上述文件同步修改
+use std::cmp::Ordering;
+
+use rosu_map::section::general::GameMode;
+use rosu_map::section::hit_objects::{
+    CurveBuffers, HitObjectKind, SliderEventType, SliderEventsIter,
+};
+use rosu_map::section::timing_points::{DifficultyPoint, TimingPoint};
+use rosu_pp::model::beatmap::BeatmapAttributesBuilder;
+
+use crate::diagnostics::parse_error;
+use crate::java::{Error, Result};
+use crate::pp::JniMapAttr;
+
+pub(crate) const PLAYFIELD_WIDTH: f32 = 512.0;
+
+const RNG_SEED: i32 = 1337;
+const BASE_SCORING_DIST: f64 = 100.0;
+const AREA_CATCHER_SIZE: f32 = 106.75;
+const ALLOWED_CATCH_RANGE: f32 = 0.8;
+const BASE_SPEED: f64 = 1.0;
+
+/// 可接的水果与 droplet (不含 tiny droplet 与香蕉), 时间未经倍速换算
+pub(crate) struct CatchObject {
+    pub x: f32,
+    pub x_offset: f32,
+    pub start_time: f64,
+    /// 接到该物件后需要 hyperdash 才能接到下一个
+    pub hyper_dash: bool,
+}
+
+impl CatchObject {
+    fn new(x: f32, x_offset: f32, start_time: f64) -> Self {
+        Self {
+            x,
+            x_offset,
+            start_time,
+            hyper_dash: false,
+        }
+    }
+
+    pub fn effective_x(&self) -> f32 {
+        (self.x + self.x_offset).clamp(0.0, PLAYFIELD_WIDTH)
+    }
+}
+
+/// [`get_catch_objects`] 的结果
+pub(crate) struct CatchObjects {
+    pub objects: Vec<CatchObject>,
+    pub fruits: usize,
+    pub droplets: usize,
+    pub tiny_droplets: usize,
+    pub bananas: usize,
+    /// 受 mods 影响的接盘宽度
+    pub catcher_width: f32,
+}
+
+/// 与 rosu-pp 相同地把谱面转为 catch 物件, 包括 HR 的位置偏移以及 hyperdash
+pub(crate) fn get_catch_objects(map_bytes: &[u8], attr: &JniMapAttr) -> Result<CatchObjects> {
+    let mut map = rosu_map::Beatmap::from_bytes(map_bytes).map_err(parse_error)?;
+    let convertible = matches!(map.mode, GameMode::Osu | GameMode::Catch);
+    if !convertible || attr.mode.unwrap_or(map.mode) != GameMode::Catch {
+        return Err(Error::from("only catch beatmap is supported"));
+    }
+
+    let cs = BeatmapAttributesBuilder::new()
+        .mode(GameMode::Catch, map.mode == GameMode::Osu)
+        .cs(map.circle_size, false)
+        .mods(attr.mods)
+        .build()
+        .cs as f32;
+    let hr = attr.hr();
+
+    let mut result = CatchObjects {
+        objects: Vec::with_capacity(map.hit_objects.len()),
+        fruits: 0,
+        droplets: 0,
+        tiny_droplets: 0,
+        bananas: 0,
+        catcher_width: catch_width(cs),
+    };
+
+    let mut bufs = CurveBuffers::default();
+    let mut ticks_buf = Vec::new();
+    let mut rng = Random::new(RNG_SEED);
+    let mut last_pos = None;
+    let mut last_start_time = 0.0;
+
+    for h in map.hit_objects.iter_mut() {
+        let start_time = h.start_time;
+        match h.kind {
+            HitObjectKind::Circle(ref c) => {
+                let mut x_offset = 0.0;
+                if hr {
+                    apply_hr_offset(
+                        c.pos.x,
+                        &mut x_offset,
+                        start_time,
+                        &mut last_pos,
+                        &mut last_start_time,
+                        &mut rng,
+                    );
+                }
+                result.fruits += 1;
+                result
+                    .objects
+                    .push(CatchObject::new(c.pos.x, x_offset, start_time));
+            }
+            HitObjectKind::Slider(ref mut s) => {
+                let x = s.pos.x.clamp(0.0, PLAYFIELD_WIDTH);
+                let beat_len = map
+                    .control_points
+                    .timing_point_at(start_time)
+                    .map_or(TimingPoint::DEFAULT_BEAT_LEN, |p| p.beat_len);
+                let slider_velocity = map
+                    .control_points
+                    .difficulty_point_at(start_time)
+                    .map_or(DifficultyPoint::DEFAULT_SLIDER_VELOCITY, |p| {
+                        p.slider_velocity
+                    });
+
+                let last_control_point = s.path.control_points().last().map_or(0.0, |p| p.pos.x);
+                let span_count = s.span_count();
+                let curve = s.path.curve_with_bufs(&mut bufs);
+                let velocity =
+                    BASE_SCORING_DIST * map.slider_multiplier / beat_len * slider_velocity;
+                let tick_dist = BASE_SCORING_DIST * map.slider_multiplier / map.slider_tick_rate
+                    * slider_velocity;
+
+                let events = SliderEventsIter::new(
+                    start_time,
+                    curve.dist() / velocity,
+                    velocity,
+                    tick_dist,
+                    curve.dist(),
+                    span_count,
+                    &mut ticks_buf,
+                );
+
+                let mut last_event_time = None;
+                let mut rng_calls = 0;
+                for e in events {
+                    if let Some(last_event_time) = last_event_time {
+                        let since_last_tick = e.time - last_event_time;
+                        if since_last_tick > 80.0 {
+                            let mut time_between_tiny = since_last_tick;
+                            while time_between_tiny > 100.0 {
+                                time_between_tiny /= 2.0;
+                            }
+
+                            let mut t = time_between_tiny;
+                            while t < since_last_tick {
+                                result.tiny_droplets += 1;
+                                rng_calls += 1;
+                                t += time_between_tiny;
+                            }
+                        }
+                    }
+                    last_event_time = Some(e.time);
+
+                    match e.kind {
+                        SliderEventType::Tick => {
+                            result.droplets += 1;
+                            rng_calls += 1;
+                        }
+                        SliderEventType::Head | SliderEventType::Repeat | SliderEventType::Tail => {
+                            result.fruits += 1;
+                        }
+                        SliderEventType::LastTick => continue,
+                    }
+                    let pos =
+                        (x + curve.position_at(e.path_progress).x).clamp(0.0, PLAYFIELD_WIDTH);
+                    result.objects.push(CatchObject::new(pos, 0.0, e.time));
+                }
+
+                last_pos = Some(s.pos.x + last_control_point);
+                last_start_time = start_time;
+                for _ in 0..rng_calls {
+                    rng.next_int();
+                }
+            }
+            HitObjectKind::Spinner(ref s) => {
+                let bananas = banana_count(start_time, s.duration);
+                result.bananas += bananas;
+                skip_bananas(&mut rng, bananas);
+            }
+            HitObjectKind::Hold(ref h) => {
+                let bananas = banana_count(start_time, h.duration);
+                result.bananas += bananas;
+                skip_bananas(&mut rng, bananas);
+            }
+        }
+    }
+
+    // 与 rosu-pp 相同, hyperdash 按 C# 不稳定排序后的顺序计算, 之后对原顺序做稳定排序
+    let objects = &mut result.objects;
+    let mut order: Vec<usize> = (0..objects.len()).collect();
+    csharp_sort(&mut order, |&a, &b| {
+        objects[a].start_time.total_cmp(&objects[b].start_time)
+    });
+    initialize_hyper_dash(result.catcher_width, objects, &order);
+    objects.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+    Ok(result)
+}
+
+fn catch_width(cs: f32) -> f32 {
+    let scale = 1.0 - 0.7 * (cs - 5.0) / 5.0;
+    AREA_CATCHER_SIZE * scale.abs() * ALLOWED_CATCH_RANGE
+}
+
+fn banana_count(start_time: f64, duration: f64) -> usize {
+    let mut spacing = duration;
+    while spacing > 100.0 {
+        spacing /= 2.0;
+    }
+    if spacing <= 0.0 {
+        return 0;
+    }
+
+    let end_time = start_time + duration;
+    let mut time = start_time;
+    let mut count = 0;
+    while time <= end_time {
+        time += spacing;
+        count += 1;
+    }
+    count
+}
+
+/// 香蕉的随机位置也会消耗随机数
+fn skip_bananas(rng: &mut Random, bananas: usize) {
+    for _ in 0..bananas {
+        rng.next_double();
+        rng.next_int();
+        rng.next_int();
+        rng.next_int();
+    }
+}
+
+fn apply_hr_offset(
+    x: f32,
+    x_offset: &mut f32,
+    start_time: f64,
+    last_pos: &mut Option<f32>,
+    last_start_time: &mut f64,
+    rng: &mut Random,
+) {
+    let mut offset_pos = x;
+
+    let Some(last_pos) = last_pos else {
+        *last_pos = Some(offset_pos);
+        *last_start_time = start_time;
+        return;
+    };
+
+    let pos_diff = offset_pos - *last_pos;
+    let time_diff = (start_time - *last_start_time) as i32;
+
+    if time_diff > 1000 {
+        *last_pos = offset_pos;
+        *last_start_time = start_time;
+        return;
+    }
+
+    if pos_diff == 0.0 {
+        apply_random_offset(&mut offset_pos, f64::from(time_diff) / 4.0, rng);
+        *x_offset = offset_pos - x;
+        return;
+    }
+
+    if pos_diff.abs() < (time_diff / 3) as f32 {
+        if pos_diff > 0.0 {
+            if offset_pos + pos_diff < PLAYFIELD_WIDTH {
+                offset_pos += pos_diff;
+            }
+        } else if offset_pos + pos_diff > 0.0 {
+            offset_pos += pos_diff;
+        }
+    }
+
+    *x_offset = offset_pos - x;
+    *last_pos = offset_pos;
+    *last_start_time = start_time;
+}
+
+fn apply_random_offset(pos: &mut f32, max_offset: f64, rng: &mut Random) {
+    let right = rng.next_bool();
+    let rand = (rng.next_double_range(0.0, max_offset.max(0.0)) as f32).min(20.0);
+
+    if right {
+        if *pos + rand <= PLAYFIELD_WIDTH {
+            *pos += rand;
+        } else {
+            *pos -= rand;
+        }
+    } else if *pos - rand >= 0.0 {
+        *pos -= rand;
+    } else {
+        *pos += rand;
+    }
+}
+
+/// `order` 为计算时使用的物件顺序
+fn initialize_hyper_dash(catcher_width: f32, objects: &mut [CatchObject], order: &[usize]) {
+    let half_catcher_width = f64::from(catcher_width / 2.0) / f64::from(ALLOWED_CATCH_RANGE);
+
+    let mut last_dir = 0;
+    let mut last_excess = half_catcher_width;
+
+    for pair in order.windows(2) {
+        let (curr, next) = (&objects[pair[0]], &objects[pair[1]]);
+        let this_dir = if next.effective_x() > curr.effective_x() {
+            1
+        } else {
+            -1
+        };
+
+        let time_to_next = next.start_time - curr.start_time - f64::from(1000.0_f32 / 60.0 / 4.0);
+        let dist_to_next = f64::from((next.effective_x() - curr.effective_x()).abs())
+            - if last_dir == this_dir {
+                last_excess
+            } else {
+                half_catcher_width
+            };
+        let dist_to_hyper = (time_to_next * BASE_SPEED - dist_to_next) as f32;
+
+        if dist_to_hyper < 0.0 {
+            objects[pair[0]].hyper_dash = true;
+            last_excess = half_catcher_width;
+        } else {
+            last_excess = f64::from(dist_to_hyper).clamp(0.0, half_catcher_width);
+        }
+        last_dir = this_dir;
+    }
+}
+
+/// C# 的不稳定排序 (introsort), 相同的元素的顺序与 osu!stable 一致
+fn csharp_sort<T, F>(keys: &mut [T], cmp: F)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    if keys.len() >= 2 {
+        let hi = keys.len() - 1;
+        intro_sort(keys, 0, hi, 2 * keys.len().ilog2(), &cmp);
+    }
+}
+
+fn intro_sort<T, F>(keys: &mut [T], lo: usize, mut hi: usize, mut depth_limit: u32, cmp: &F)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    const INTRO_SORT_SIZE_THRESHOLD: usize = 16;
+
+    while hi > lo {
+        let partition_size = hi - lo + 1;
+
+        if partition_size <= INTRO_SORT_SIZE_THRESHOLD {
+            match partition_size {
+                1 => {}
+                2 => swap_if_greater(keys, cmp, lo, hi),
+                3 => {
+                    swap_if_greater(keys, cmp, lo, hi - 1);
+                    swap_if_greater(keys, cmp, lo, hi);
+                    swap_if_greater(keys, cmp, hi - 1, hi);
+                }
+                _ => insertion_sort(keys, lo, hi, cmp),
+            }
+            break;
+        }
+
+        if depth_limit == 0 {
+            heap_sort(keys, lo, hi, cmp);
+            break;
+        }
+
+        depth_limit -= 1;
+        let p = pick_pivot_and_partition(keys, lo, hi, cmp);
+        intro_sort(keys, p + 1, hi, depth_limit, cmp);
+        hi = p - 1;
+    }
+}
+
+fn pick_pivot_and_partition<T, F>(keys: &mut [T], lo: usize, hi: usize, cmp: &F) -> usize
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let mid = lo + (hi - lo) / 2;
+    swap_if_greater(keys, cmp, lo, mid);
+    swap_if_greater(keys, cmp, lo, hi);
+    swap_if_greater(keys, cmp, mid, hi);
+    swap(keys, mid, hi - 1);
+    let mut left = lo;
+    let mut right = hi - 1;
+    let pivot_idx = right;
+
+    while left < right {
+        loop {
+            left += 1;
+            if cmp(&keys[left], &keys[pivot_idx]).is_ge() {
+                break;
+            }
+        }
+        loop {
+            right -= 1;
+            if cmp(&keys[pivot_idx], &keys[right]).is_ge() {
+                break;
+            }
+        }
+
+        if left >= right {
+            break;
+        }
+        swap(keys, left, right);
+    }
+
+    swap(keys, left, hi - 1);
+    left
+}
+
+fn insertion_sort<T, F>(keys: &mut [T], lo: usize, hi: usize, cmp: &F)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    for i in lo..hi {
+        let t = &keys[i + 1];
+        let shift = keys[lo..=i]
+            .iter()
+            .rev()
+            .take_while(|curr| cmp(t, curr).is_lt())
+            .count();
+        keys[i + 1 - shift..=i + 1].rotate_right(1);
+    }
+}
+
+fn heap_sort<T, F>(keys: &mut [T], lo: usize, hi: usize, cmp: &F)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let n = hi - lo + 1;
+
+    for i in (1..=n / 2).rev() {
+        down_heap(keys, i, n, lo, cmp);
+    }
+
+    for i in (2..=n).rev() {
+        swap(keys, lo, lo + i - 1);
+        down_heap(keys, 1, i - 1, lo, cmp);
+    }
+}
+
+fn down_heap<T, F>(keys: &mut [T], mut i: usize, n: usize, lo: usize, cmp: &F)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    while i <= n / 2 {
+        let mut child = 2 * i;
+
+        if child < n && cmp(&keys[lo + child - 1], &keys[lo + child]).is_lt() {
+            child += 1;
+        }
+
+        if cmp(&keys[lo + i - 1], &keys[lo + child - 1]).is_ge() {
+            break;
+        }
+
+        keys.swap(lo + i - 1, lo + child - 1);
+        i = child;
+    }
+}
+
+fn swap_if_greater<T, F>(keys: &mut [T], cmp: &F, a: usize, b: usize)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    if a != b && cmp(&keys[a], &keys[b]).is_gt() {
+        keys.swap(a, b);
+    }
+}
+
+fn swap<T>(keys: &mut [T], i: usize, j: usize) {
+    if i != j {
+        keys.swap(i, j);
+    }
+}
+
+/// osu!stable 使用的 C# 随机数, HR 的位置偏移依赖它
+struct Random {
+    x: u32,
+    y: u32,
+    z: u32,
+    w: u32,
+    bit_buf: u32,
+    bit_idx: i32,
+}
+
+impl Random {
+    const INT_TO_REAL: f64 = 1.0 / (i32::MAX as f64 + 1.0);
+    const INT_MASK: u32 = 0x7F_FF_FF_FF;
+
+    fn new(seed: i32) -> Self {
+        Self {
+            x: seed as u32,
+            y: 842_502_087,
+            z: 3_579_807_591,
+            w: 273_326_509,
+            bit_buf: 0,
+            bit_idx: 32,
+        }
+    }
+
+    fn gen_unsigned(&mut self) -> u32 {
+        let t = self.x ^ (self.x << 11);
+        self.x = self.y;
+        self.y = self.z;
+        self.z = self.w;
+        self.w = self.w ^ (self.w >> 19) ^ t ^ (t >> 8);
+        self.w
+    }
+
+    fn next_int(&mut self) -> i32 {
+        (Self::INT_MASK & self.gen_unsigned()) as i32
+    }
+
+    fn next_double(&mut self) -> f64 {
+        Self::INT_TO_REAL * f64::from(self.next_int())
+    }
+
+    fn next_double_range(&mut self, min: f64, max: f64) -> i32 {
+        (min + self.next_double() * (max - min)) as i32
+    }
+
+    fn next_bool(&mut self) -> bool {
+        if self.bit_idx == 32 {
+            self.bit_buf = self.gen_unsigned();
+            self.bit_idx = 1;
+        } else {
+            self.bit_idx += 1;
+            self.bit_buf >>= 1;
+        }
+        (self.bit_buf & 1) == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rosu_pp::any::DifficultyAttributes;
+    use rosu_pp::Difficulty;
+
+    use super::*;
+
+    /// 滑条尾与圆圈在同一时刻, 两者的先后决定 hyperdash
+    fn map() -> String {
+        let mut text = String::from(
+            "osu file format v14
+
+[General]
+Mode: 0
+
+[Difficulty]
+CircleSize:5
+SliderMultiplier:1.4
+SliderTickRate:1
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+",
+        );
+        for i in 0..10 {
+            let time = i * 700;
+            text.push_str(&format!("0,192,{time},2,0,L|140:192,1,140\n"));
+            text.push_str(&format!("500,192,{},1,0,0:0:0:0:\n", time + 500));
+        }
+        text.push_str("256,192,7000,12,0,8000,0:0:0:0:\n");
+        text
+    }
+
+    #[test]
+    fn counts_match_rosu_pp() {
+        let text = map();
+        for mods in [0, 1 << 4] {
+            let attr = JniMapAttr {
+                mode: Some(GameMode::Catch),
+                mods,
+                ..Default::default()
+            };
+            let catch = get_catch_objects(text.as_bytes(), &attr).unwrap();
+
+            let mut map = rosu_pp::Beatmap::from_bytes(text.as_bytes()).unwrap();
+            assert!(map.convert_in_place(GameMode::Catch).success());
+            let DifficultyAttributes::Catch(expected) =
+                Difficulty::new().mods(mods).calculate(&map)
+            else {
+                panic!("expected catch attributes");
+            };
+            assert_eq!(catch.fruits, expected.n_fruits as usize);
+            assert_eq!(catch.droplets, expected.n_droplets as usize);
+            assert_eq!(catch.tiny_droplets, expected.n_tiny_droplets as usize);
+            assert_eq!(catch.objects.len(), catch.fruits + catch.droplets);
+            assert_eq!(catch.bananas, 17);
+        }
+    }
+
+    #[test]
+    fn hyper_dash_uses_csharp_order() {
+        let attr = JniMapAttr {
+            mode: Some(GameMode::Catch),
+            ..Default::default()
+        };
+        let catch = get_catch_objects(map().as_bytes(), &attr).unwrap();
+        let count = catch.objects.iter().filter(|h| h.hyper_dash).count();
+        assert_eq!(count, 18);
+
+        // 按稳定排序的顺序计算时结果不同
+        let mut objects = catch.objects;
+        objects.iter_mut().for_each(|h| h.hyper_dash = false);
+        let order: Vec<usize> = (0..objects.len()).collect();
+        initialize_hyper_dash(catch.catcher_width, &mut objects, &order);
+        assert_eq!(objects.iter().filter(|h| h.hyper_dash).count(), 19);
+    }
+
+    #[test]
+    fn csharp_sort_is_unstable() {
+        let keys: Vec<(u32, usize)> = (0..40).map(|i| ((i % 4) as u32, i)).collect();
+        let mut sorted = keys.clone();
+        csharp_sort(&mut sorted, |a, b| a.0.cmp(&b.0));
+        assert!(sorted.windows(2).all(|w| w[0].0 <= w[1].0));
+
+        let mut stable = keys;
+        stable.sort_by_key(|k| k.0);
+        assert_ne!(sorted, stable);
+
+        // 不超过 16 个时为插入排序, 保持原顺序
+        let mut small: Vec<(u32, usize)> = (0..16).map(|i| ((i % 2) as u32, i)).collect();
+        let mut expected = small.clone();
+        csharp_sort(&mut small, |a, b| a.0.cmp(&b.0));
+        expected.sort_by_key(|k| k.0);
+        assert_eq!(small, expected);
+    }
+}
@@ -0,0 +1,527 @@
+use bytes::BufMut;
+use jni::objects::{JByteArray, JObjectArray};
+use jni::JNIEnv;
+use rosu_map::section::general::GameMode;
+use rosu_map::section::hit_objects::HitObjectKind;
+use rosu_map::Beatmap;
+
+use crate::analysis::nearest_snap;
+use crate::beatmap::{get_full_map, object_times};
+use crate::java::{Error, Result};
+use crate::pp::difficulty_range;
+use crate::{vec_add_str, StatusFlag};
+
+const SEVERITY_MINOR: u8 = 0;
+const SEVERITY_WARNING: u8 = 1;
+const SEVERITY_PROBLEM: u8 = 2;
+
+const CHECK_UNSNAPPED: u8 = 0;
+const CHECK_OBJECT_IN_BREAK: u8 = 1;
+const CHECK_OVERLAPPING: u8 = 2;
+const CHECK_SHORT_DRAIN: u8 = 3;
+const CHECK_MISSING_PREVIEW: u8 = 4;
+const CHECK_INCONSISTENT_METADATA: u8 = 5;
+const CHECK_ABNORMAL_SLIDER_VELOCITY: u8 = 6;
+const CHECK_SHORT_SPINNER: u8 = 7;
+
+/// 编辑器可以使用的分音, 由粗到细
+const EDITOR_DIVISORS: [u32; 8] = [1, 2, 3, 4, 6, 8, 12, 16];
+/// 与最近的分音线相差超过该值 (ms) 视为 unsnapped, 谱面中的时间为整数毫秒
+const UNSNAP_TOLERANCE: f64 = 1.0;
+/// 排行标准要求的最短 drain time (ms)
+const MIN_DRAIN_TIME: f64 = 30_000.0;
+/// 超出该范围的 slider velocity 视为异常, 只检查 osu 与 catch
+const SLIDER_VELOCITY_RANGE: (f64, f64) = (0.5, 2.0);
+/// 检查转盘时假设的转速 (圈 / s) 与开始转动前的准备时间 (ms)
+const SPINNER_RPS: f64 = 8.0;
+const SPINNER_SPIN_UP: f64 = 250.0;
+
+/// 类似 AiMod 的谱面检查
+///
+/// ` [(none)u8 | (size)i32 | issue * size] `
+/// - issue: `[(severity)u8 | (check)u8 | (map index)i32 | (time)f64 | message]`
+///
+/// severity 为 0 minor, 1 warning, 2 problem; check 依次为 unsnapped, 休息段内的物件, 重叠物件,
+/// drain time 过短, 未设置预览点, 难度间元数据不一致, 异常的 slider velocity, 转盘过短;
+/// 时间为谱面时间, 不针对某个时间点的问题为 NaN
+///
+/// 物件 (包括滑条折返与尾, 长条尾) 与最近的分音线 (1/1 到 1/16) 相差超过 1ms 时为 unsnapped, 并报告该分音
+pub fn check_beatmap(env: &JNIEnv, local_map: &JByteArray) -> Result<Vec<u8>> {
+    let mut map = get_full_map(env, local_map)?;
+    let mut issues = Vec::new();
+    check_map(&mut map, 0, &mut issues);
+    Ok(issues_to_bytes(&issues))
+}
+
+/// 检查同一图组的多个难度, 除每个难度的检查外还会比较难度间的元数据, 结果同 [`check_beatmap`]
+///
+/// map index 为难度在参数中的序号, 不一致的元数据记录在与第一个难度不同的难度上
+pub fn check_mapset(env: &mut JNIEnv, local_maps: &JObjectArray) -> Result<Vec<u8>> {
+    let size = env.get_array_length(local_maps)?;
+    if size == 0 {
+        return Err(Error::from("no beatmaps"));
+    }
+
+    let mut maps = Vec::with_capacity(size as usize);
+    for i in 0..size {
+        let local_map = JByteArray::from(env.get_object_array_element(local_maps, i)?);
+        maps.push(get_full_map(env, &local_map)?);
+    }
+
+    let mut issues = Vec::new();
+    for (i, map) in maps.iter_mut().enumerate() {
+        check_map(map, i as i32, &mut issues);
+    }
+    check_metadata(&maps, &mut issues);
+    Ok(issues_to_bytes(&issues))
+}
+
+struct Issue {
+    severity: u8,
+    check: u8,
+    map: i32,
+    time: f64,
+    message: String,
+}
+
+fn issues_to_bytes(issues: &[Issue]) -> Vec<u8> {
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::None.bits());
+    result.put_i32(issues.len() as i32);
+    for issue in issues {
+        result.put_u8(issue.severity);
+        result.put_u8(issue.check);
+        result.put_i32(issue.map);
+        result.put_f64(issue.time);
+        vec_add_str(&issue.message, &mut result);
+    }
+    result
+}
+
+fn check_map(map: &mut Beatmap, index: i32, issues: &mut Vec<Issue>) {
+    let mut push = |severity, check, time, message| {
+        issues.push(Issue {
+            severity,
+            check,
+            map: index,
+            time,
+            message,
+        })
+    };
+    let times = object_times(map);
+
+    // 滑条的折返与尾, 以及长条的尾也需要对齐
+    if !map.control_points.timing_points.is_empty() {
+        for (h, (start, end)) in map.hit_objects.iter().zip(&times) {
+            let mut points = vec![("object", *start)];
+            match h.kind {
+                HitObjectKind::Slider(ref s) => {
+                    let spans = s.span_count();
+                    let span_duration = (end - start) / f64::from(spans);
+                    points.extend(
+                        (1..spans).map(|i| ("slider repeat", start + f64::from(i) * span_duration)),
+                    );
+                    points.push(("slider end", *end));
+                }
+                HitObjectKind::Hold(_) => points.push(("hold end", *end)),
+                _ => {}
+            }
+
+            for (name, time) in points {
+                let Some((divisor, offset)) =
+                    nearest_snap(&map.control_points, time, &EDITOR_DIVISORS)
+                else {
+                    continue;
+                };
+                if offset.abs() > UNSNAP_TOLERANCE {
+                    let message = format!(
+                        "unsnapped {name}, {offset:+.0}ms from the nearest 1/{divisor} tick"
+                    );
+                    push(SEVERITY_PROBLEM, CHECK_UNSNAPPED, time, message);
+                }
+            }
+        }
+    }
+
+    for (start, end) in &times {
+        let in_break = map.breaks.iter().any(|b| {
+            (*start > b.start_time && *start < b.end_time)
+                || (*end > b.start_time && *end < b.end_time)
+        });
+        if in_break {
+            let message = "object during a break".to_owned();
+            push(SEVERITY_PROBLEM, CHECK_OBJECT_IN_BREAK, *start, message);
+        }
+    }
+
+    // mania 只比较同一列的物件
+    let columns = if map.mode == GameMode::Mania {
+        (map.circle_size.round() as usize).max(1)
+    } else {
+        1
+    };
+    let mut last: Vec<Option<(f64, f64)>> = vec![None; columns];
+    for (h, (start, end)) in map.hit_objects.iter().zip(&times) {
+        let column = match h.kind {
+            HitObjectKind::Circle(ref c) if columns > 1 => column(c.pos.x, columns),
+            HitObjectKind::Hold(ref h) => column(h.pos_x, columns),
+            _ => 0,
+        };
+        if let Some((last_start, last_end)) = last[column] {
+            if *start == last_start || *start < last_end {
+                let message = format!("object overlaps the object at {last_start}");
+                push(SEVERITY_PROBLEM, CHECK_OVERLAPPING, *start, message);
+            }
+        }
+        last[column] = Some((*start, *end));
+    }
+
+    if let (Some(first), Some(last)) = (times.first(), times.iter().map(|t| t.1).reduce(f64::max)) {
+        let breaks: f64 = map.breaks.iter().map(|b| b.end_time - b.start_time).sum();
+        let drain = last - first.0 - breaks;
+        if drain < MIN_DRAIN_TIME {
+            let message = format!(
+                "drain time {:.1}s is shorter than {}s",
+                drain / 1000.0,
+                MIN_DRAIN_TIME / 1000.0
+            );
+            push(SEVERITY_PROBLEM, CHECK_SHORT_DRAIN, f64::NAN, message);
+        }
+    }
+
+    if map.preview_time < 0 {
+        let message = "preview point is not set".to_owned();
+        push(SEVERITY_WARNING, CHECK_MISSING_PREVIEW, f64::NAN, message);
+    }
+
+    if matches!(map.mode, GameMode::Osu | GameMode::Catch) {
+        let (min, max) = SLIDER_VELOCITY_RANGE;
+        for point in &map.control_points.difficulty_points {
+            if point.slider_velocity < min || point.slider_velocity > max {
+                let message = format!("abnormal slider velocity {:.2}x", point.slider_velocity);
+                push(
+                    SEVERITY_WARNING,
+                    CHECK_ABNORMAL_SLIDER_VELOCITY,
+                    point.time,
+                    message,
+                );
+            }
+        }
+    }
+
+    if map.mode == GameMode::Osu {
+        let od = f64::from(map.overall_difficulty);
+        let rps = difficulty_range(od, 3.0, 5.0, 7.5);
+        for h in &map.hit_objects {
+            let HitObjectKind::Spinner(ref s) = h.kind else {
+                continue;
+            };
+            let required = s.duration / 1000.0 * rps;
+            let possible = (s.duration - SPINNER_SPIN_UP).max(0.0) / 1000.0 * SPINNER_RPS;
+            if possible < required {
+                let message = format!(
+                    "spinner of {:.0}ms is too short for OD {}",
+                    s.duration, map.overall_difficulty
+                );
+                push(SEVERITY_WARNING, CHECK_SHORT_SPINNER, h.start_time, message);
+            }
+        }
+    }
+}
+
+fn column(x: f32, columns: usize) -> usize {
+    let width = 512.0 / columns as f32;
+    ((x / width).floor().max(0.0) as usize).min(columns - 1)
+}
+
+/// 与第一个难度比较, 预览点不同为 warning, tags 不同为 minor
+fn check_metadata(maps: &[Beatmap], issues: &mut Vec<Issue>) {
+    type Field = (&'static str, u8, fn(&Beatmap) -> String);
+    const FIELDS: [Field; 10] = [
+        ("title", SEVERITY_PROBLEM, |m| m.title.clone()),
+        ("titleUnicode", SEVERITY_PROBLEM, |m| {
+            m.title_unicode.clone()
+        }),
+        ("artist", SEVERITY_PROBLEM, |m| m.artist.clone()),
+        ("artistUnicode", SEVERITY_PROBLEM, |m| {
+            m.artist_unicode.clone()
+        }),
+        ("creator", SEVERITY_PROBLEM, |m| m.creator.clone()),
+        ("source", SEVERITY_PROBLEM, |m| m.source.clone()),
+        ("audioFile", SEVERITY_PROBLEM, |m| m.audio_file.clone()),
+        ("beatmapSetId", SEVERITY_PROBLEM, |m| {
+            m.beatmap_set_id.to_string()
+        }),
+        ("previewTime", SEVERITY_WARNING, |m| {
+            m.preview_time.to_string()
+        }),
+        ("tags", SEVERITY_MINOR, |m| m.tags.clone()),
+    ];
+
+    let Some((first, rest)) = maps.split_first() else {
+        return;
+    };
+    for (name, severity, get) in FIELDS {
+        let expected = get(first);
+        for (i, map) in rest.iter().enumerate() {
+            let value = get(map);
+            if value != expected {
+                issues.push(Issue {
+                    severity,
+                    check: CHECK_INCONSISTENT_METADATA,
+                    map: i as i32 + 1,
+                    time: f64::NAN,
+                    message: format!("{name} \"{value}\" differs from \"{expected}\""),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 300 bpm, 红线从 0 开始
+    fn map(general: &str, timing: &str, objects: &str) -> Beatmap {
+        let text = format!(
+            "osu file format v14
+
+[General]
+{general}
+
+[Difficulty]
+OverallDifficulty:10
+SliderMultiplier:1.4
+
+[TimingPoints]
+0,200,4,2,0,100,1,0
+{timing}
+
+[HitObjects]
+{objects}"
+        );
+        Beatmap::from_bytes(text.as_bytes()).unwrap()
+    }
+
+    fn issues(map: &mut Beatmap, check: u8) -> Vec<(u8, f64, String)> {
+        let mut issues = Vec::new();
+        check_map(map, 0, &mut issues);
+        issues
+            .into_iter()
+            .filter(|issue| issue.check == check)
+            .map(|issue| (issue.severity, issue.time, issue.message))
+            .collect()
+    }
+
+    #[test]
+    fn unsnapped_at_high_bpm() {
+        let mut map = map(
+            "Mode: 0",
+            "",
+            "256,192,1000,1,0,0:0:0:0:
+256,192,1050,1,0,0:0:0:0:
+256,192,1253,1,0,0:0:0:0:
+256,192,1414,1,0,0:0:0:0:
+256,192,1617,1,0,0:0:0:0:
+256,192,2000,2,0,L|326:192,1,70
+256,192,3000,2,0,L|331:192,1,75
+",
+        );
+
+        assert_eq!(
+            issues(&mut map, CHECK_UNSNAPPED),
+            [
+                (
+                    SEVERITY_PROBLEM,
+                    1253.0,
+                    "unsnapped object, +3ms from the nearest 1/4 tick".to_owned()
+                ),
+                // 离 1/16 的线 1.5ms
+                (
+                    SEVERITY_PROBLEM,
+                    1414.0,
+                    "unsnapped object, +2ms from the nearest 1/16 tick".to_owned()
+                ),
+                (
+                    SEVERITY_PROBLEM,
+                    3000.0 + 75.0 / 0.7,
+                    "unsnapped slider end, -5ms from the nearest 1/16 tick".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn object_in_break() {
+        let mut map = map(
+            "Mode: 0",
+            "",
+            "256,192,1000,1,0,0:0:0:0:
+256,192,3000,1,0,0:0:0:0:
+256,192,6000,1,0,0:0:0:0:
+",
+        );
+        map.breaks.push(rosu_map::section::events::BreakPeriod {
+            start_time: 2000.0,
+            end_time: 5000.0,
+        });
+
+        let issues = issues(&mut map, CHECK_OBJECT_IN_BREAK);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].1, 3000.0);
+    }
+
+    #[test]
+    fn overlapping_objects() {
+        let mut osu = map(
+            "Mode: 0",
+            "",
+            "256,192,1000,1,0,0:0:0:0:
+100,100,1000,1,0,0:0:0:0:
+256,192,2000,12,0,3000,0:0:0:0:
+256,192,2500,1,0,0:0:0:0:
+",
+        );
+        let times: Vec<_> = issues(&mut osu, CHECK_OVERLAPPING)
+            .into_iter()
+            .map(|issue| issue.1)
+            .collect();
+        assert_eq!(times, [1000.0, 2500.0]);
+
+        // mania 不同列的物件可以同时
+        let mut mania = map(
+            "Mode: 3",
+            "",
+            "64,192,1000,1,0,0:0:0:0:
+448,192,1000,1,0,0:0:0:0:
+448,192,1000,1,0,0:0:0:0:
+",
+        );
+        mania.circle_size = 4.0;
+        let times: Vec<_> = issues(&mut mania, CHECK_OVERLAPPING)
+            .into_iter()
+            .map(|issue| issue.1)
+            .collect();
+        assert_eq!(times, [1000.0]);
+    }
+
+    #[test]
+    fn drain_and_preview() {
+        let mut short = map(
+            "Mode: 0",
+            "",
+            "256,192,1000,1,0,0:0:0:0:\n256,192,2000,1,0,0:0:0:0:\n",
+        );
+        let drain = issues(&mut short, CHECK_SHORT_DRAIN);
+        assert_eq!(drain.len(), 1);
+        assert!(drain[0].1.is_nan());
+        assert_eq!(drain[0].2, "drain time 1.0s is shorter than 30s");
+        assert_eq!(issues(&mut short, CHECK_MISSING_PREVIEW).len(), 1);
+
+        let mut long = map(
+            "PreviewTime: 1000\nMode: 0",
+            "",
+            "256,192,1000,1,0,0:0:0:0:\n256,192,41000,1,0,0:0:0:0:\n",
+        );
+        assert!(issues(&mut long, CHECK_SHORT_DRAIN).is_empty());
+        assert!(issues(&mut long, CHECK_MISSING_PREVIEW).is_empty());
+    }
+
+    #[test]
+    fn abnormal_slider_velocity() {
+        let timing = "1000,-25,4,2,0,100,0,0\n2000,-100,4,2,0,100,0,0\n3000,-250,4,2,0,100,0,0";
+        let objects = "256,192,1000,1,0,0:0:0:0:\n";
+
+        let mut osu = map("Mode: 0", timing, objects);
+        let issues_osu = issues(&mut osu, CHECK_ABNORMAL_SLIDER_VELOCITY);
+        assert_eq!(
+            issues_osu,
+            [
+                (
+                    SEVERITY_WARNING,
+                    1000.0,
+                    "abnormal slider velocity 4.00x".to_owned()
+                ),
+                (
+                    SEVERITY_WARNING,
+                    3000.0,
+                    "abnormal slider velocity 0.40x".to_owned()
+                ),
+            ]
+        );
+
+        let mut taiko = map("Mode: 1", timing, objects);
+        assert!(issues(&mut taiko, CHECK_ABNORMAL_SLIDER_VELOCITY).is_empty());
+    }
+
+    /// OD 10 要求 7.5 rps, 假设 8 rps 且需要 250ms 开始转动
+    #[test]
+    fn short_spinner() {
+        let mut map = map(
+            "Mode: 0",
+            "",
+            "256,192,1000,12,0,1500,0:0:0:0:
+256,192,2000,12,0,5000,0:0:0:0:
+256,192,6000,12,0,11000,0:0:0:0:
+",
+        );
+        let issues = issues(&mut map, CHECK_SHORT_SPINNER);
+        assert_eq!(
+            issues,
+            [
+                (
+                    SEVERITY_WARNING,
+                    1000.0,
+                    "spinner of 500ms is too short for OD 10".to_owned()
+                ),
+                (
+                    SEVERITY_WARNING,
+                    2000.0,
+                    "spinner of 3000ms is too short for OD 10".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn inconsistent_metadata() {
+        let first = map("Mode: 0", "", "");
+        let mut second = map("Mode: 0", "", "");
+        second.title = "Other".to_owned();
+        second.tags = "tag".to_owned();
+        let mut third = map("Mode: 0", "", "");
+        third.preview_time = 1000;
+
+        let mut issues = Vec::new();
+        check_metadata(&[first, second, third], &mut issues);
+        let issues: Vec<_> = issues
+            .into_iter()
+            .map(|issue| (issue.severity, issue.check, issue.map, issue.message))
+            .collect();
+
+        assert_eq!(
+            issues,
+            [
+                (
+                    SEVERITY_PROBLEM,
+                    CHECK_INCONSISTENT_METADATA,
+                    1,
+                    "title \"Other\" differs from \"\"".to_owned()
+                ),
+                (
+                    SEVERITY_WARNING,
+                    CHECK_INCONSISTENT_METADATA,
+                    2,
+                    "previewTime \"1000\" differs from \"-1\"".to_owned()
+                ),
+                (
+                    SEVERITY_MINOR,
+                    CHECK_INCONSISTENT_METADATA,
+                    1,
+                    "tags \"tag\" differs from \"\"".to_owned()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,376 @@
+use std::error::Error as StdError;
+
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::Section;
+use rosu_map::{
+    Beatmap, BeatmapState, DecodeBeatmap, DecodeState, ParseBeatmapError, LATEST_FORMAT_VERSION,
+};
+
+use crate::encoding::read_map_bytes;
+use crate::java::{Error, Result};
+use crate::{vec_add_str, StatusFlag};
+
+const WARNING_UNKNOWN_SECTION: u8 = 0;
+const WARNING_INVALID_LINE: u8 = 1;
+const WARNING_MISSING_TIMING_POINTS: u8 = 2;
+const WARNING_NO_HIT_OBJECTS: u8 = 3;
+const WARNING_UNSUPPORTED_VERSION: u8 = 4;
+
+/// 仍在使用的最早的格式版本
+const MIN_FORMAT_VERSION: i32 = 3;
+const VERSION_PREFIX: &str = "osu file format v";
+
+/// 解析谱面并报告文件中的问题
+///
+/// ` [(none)u8 | (format version)i32 | (size)i32 | [(kind)u8 | (line)i32 | message] * size] `
+///
+/// kind 依次为未知的分区, 无法解析的行, 没有红线, 没有物件, 不支持的格式版本;
+/// line 从 1 开始, 与行无关的问题为 0; 缺少版本号时 format version 为 0
+///
+/// 解析器会跳过无法解析的行, `strict` 为 true 时有任何问题都会返回错误
+pub fn parse_diagnostics(env: &JNIEnv, local_map: &JByteArray, strict: bool) -> Result<Vec<u8>> {
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let (format_version, warnings) = diagnose(&map_bytes)?;
+
+    if strict && !warnings.is_empty() {
+        let message = warnings
+            .iter()
+            .map(Warning::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        return Err(Error::from(message));
+    }
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::None.bits());
+    result.put_i32(format_version.unwrap_or(0));
+    result.put_i32(warnings.len() as i32);
+    for w in &warnings {
+        result.put_u8(w.kind);
+        result.put_i32(w.line as i32);
+        vec_add_str(&w.message, &mut result);
+    }
+    Ok(result)
+}
+
+/// 解析失败时附带原因, 代替 io 错误本身的信息
+pub(crate) fn parse_error(err: std::io::Error) -> Error {
+    Error::from(format!(
+        "failed to parse beatmap: {}",
+        error_chain_message(&err)
+    ))
+}
+
+struct Warning {
+    kind: u8,
+    /// 从 1 开始, 0 表示与行无关
+    line: usize,
+    message: String,
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}: {}", self.line, self.message)
+        } else {
+            f.write_str(&self.message)
+        }
+    }
+}
+
+fn diagnose(map_bytes: &[u8]) -> Result<(Option<i32>, Vec<Warning>)> {
+    let diagnosed: DiagnosedMap = rosu_map::from_bytes(map_bytes).map_err(parse_error)?;
+    let text = String::from_utf8_lossy(map_bytes);
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let mut warnings = Vec::new();
+
+    let header = lines
+        .iter()
+        .enumerate()
+        .find(|(_, line)| !line.trim_start_matches('\u{feff}').is_empty());
+    let format_version = match header {
+        Some((i, line)) => match line
+            .trim_start_matches('\u{feff}')
+            .strip_prefix(VERSION_PREFIX)
+        {
+            Some(version) => match version.trim().parse::<i32>() {
+                Ok(version) => {
+                    if !(MIN_FORMAT_VERSION..=LATEST_FORMAT_VERSION).contains(&version) {
+                        warnings.push(Warning {
+                            kind: WARNING_UNSUPPORTED_VERSION,
+                            line: i + 1,
+                            message: format!("unsupported file format version {version}"),
+                        });
+                    }
+                    Some(version)
+                }
+                Err(_) => {
+                    warnings.push(Warning {
+                        kind: WARNING_UNSUPPORTED_VERSION,
+                        line: i + 1,
+                        message: format!("invalid file format version \"{version}\""),
+                    });
+                    None
+                }
+            },
+            None => {
+                warnings.push(Warning {
+                    kind: WARNING_UNSUPPORTED_VERSION,
+                    line: i + 1,
+                    message: "missing file format version".to_owned(),
+                });
+                None
+            }
+        },
+        None => None,
+    };
+
+    // 未知分区内的行会交给前一个分区解析, 只报告分区本身
+    let mut in_unknown = Vec::with_capacity(lines.len());
+    let mut unknown = false;
+    for (i, line) in lines.iter().enumerate() {
+        if line.starts_with('[') && line.ends_with(']') {
+            unknown = is_unknown_section(line);
+            if unknown {
+                warnings.push(Warning {
+                    kind: WARNING_UNKNOWN_SECTION,
+                    line: i + 1,
+                    message: format!("unknown section {line}"),
+                });
+            }
+        }
+        in_unknown.push(unknown);
+    }
+
+    // 解析器只给出行的内容, 按顺序在原文中查找行号
+    let mut next = 0;
+    for (content, section) in diagnosed.invalid_lines {
+        if is_unknown_section(&content) {
+            continue;
+        }
+        let line = lines[next..]
+            .iter()
+            .position(|line| *line == content)
+            .map_or(0, |i| {
+                next += i + 1;
+                next
+            });
+        if line > 0 && in_unknown[line - 1] {
+            continue;
+        }
+        warnings.push(Warning {
+            kind: WARNING_INVALID_LINE,
+            line,
+            message: format!("invalid line in [{section}]: \"{content}\""),
+        });
+    }
+
+    if diagnosed.map.control_points.timing_points.is_empty() {
+        warnings.push(Warning {
+            kind: WARNING_MISSING_TIMING_POINTS,
+            line: 0,
+            message: "beatmap has no timing points".to_owned(),
+        });
+    }
+    if diagnosed.map.hit_objects.is_empty() {
+        warnings.push(Warning {
+            kind: WARNING_NO_HIT_OBJECTS,
+            line: 0,
+            message: "beatmap has no hit objects".to_owned(),
+        });
+    }
+
+    warnings.sort_by_key(|w| if w.line == 0 { usize::MAX } else { w.line });
+    Ok((format_version, warnings))
+}
+
+fn is_unknown_section(line: &str) -> bool {
+    line.starts_with('[') && line.ends_with(']') && Section::try_from_line(line).is_none()
+}
+
+fn error_chain_message(err: &dyn StdError) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        message.push_str(": ");
+        message.push_str(&err.to_string());
+        source = err.source();
+    }
+    message
+}
+
+type ParseResult = std::result::Result<(), ParseBeatmapError>;
+
+/// 与 [`Beatmap`] 相同地解析, 同时记录被跳过的行
+struct DiagnosedMap {
+    map: Beatmap,
+    invalid_lines: Vec<(String, &'static str)>,
+}
+
+struct DiagnosedState {
+    inner: BeatmapState,
+    invalid_lines: Vec<(String, &'static str)>,
+}
+
+impl DecodeState for DiagnosedState {
+    fn create(version: i32) -> Self {
+        Self {
+            inner: BeatmapState::create(version),
+            invalid_lines: Vec::new(),
+        }
+    }
+}
+
+impl From<DiagnosedState> for DiagnosedMap {
+    fn from(state: DiagnosedState) -> Self {
+        Self {
+            map: state.inner.into(),
+            invalid_lines: state.invalid_lines,
+        }
+    }
+}
+
+impl DiagnosedState {
+    /// rosu-map 的错误信息是拼接的 Debug 输出, 只记录行所在的分区
+    fn record(
+        &mut self,
+        line: &str,
+        section: &'static str,
+        f: fn(&mut BeatmapState, &str) -> ParseResult,
+    ) -> ParseResult {
+        if f(&mut self.inner, line).is_err() {
+            self.invalid_lines.push((line.to_owned(), section));
+        }
+        Ok(())
+    }
+}
+
+impl DecodeBeatmap for DiagnosedMap {
+    type Error = ParseBeatmapError;
+    type State = DiagnosedState;
+
+    fn parse_general(state: &mut Self::State, line: &str) -> ParseResult {
+        state.record(line, "General", Beatmap::parse_general)
+    }
+
+    fn parse_editor(state: &mut Self::State, line: &str) -> ParseResult {
+        state.record(line, "Editor", Beatmap::parse_editor)
+    }
+
+    fn parse_metadata(state: &mut Self::State, line: &str) -> ParseResult {
+        state.record(line, "Metadata", Beatmap::parse_metadata)
+    }
+
+    fn parse_difficulty(state: &mut Self::State, line: &str) -> ParseResult {
+        state.record(line, "Difficulty", Beatmap::parse_difficulty)
+    }
+
+    fn parse_events(state: &mut Self::State, line: &str) -> ParseResult {
+        state.record(line, "Events", Beatmap::parse_events)
+    }
+
+    fn parse_timing_points(state: &mut Self::State, line: &str) -> ParseResult {
+        state.record(line, "TimingPoints", Beatmap::parse_timing_points)
+    }
+
+    fn parse_colors(state: &mut Self::State, line: &str) -> ParseResult {
+        state.record(line, "Colours", Beatmap::parse_colors)
+    }
+
+    fn parse_hit_objects(state: &mut Self::State, line: &str) -> ParseResult {
+        state.record(line, "HitObjects", Beatmap::parse_hit_objects)
+    }
+
+    fn parse_variables(_: &mut Self::State, _: &str) -> ParseResult {
+        Ok(())
+    }
+
+    fn parse_catch_the_beat(_: &mut Self::State, _: &str) -> ParseResult {
+        Ok(())
+    }
+
+    fn parse_mania(_: &mut Self::State, _: &str) -> ParseResult {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "osu file format v14
+
+[General]
+Mode: 0
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+256,192,0,1,0,0:0:0:0:
+256,192,500,1,0,0:0:0:0:
+";
+
+    fn warnings(text: &str) -> (Option<i32>, Vec<(u8, usize)>) {
+        let (version, warnings) = diagnose(text.as_bytes()).unwrap();
+        let warnings = warnings.iter().map(|w| (w.kind, w.line)).collect();
+        (version, warnings)
+    }
+
+    #[test]
+    fn valid_map() {
+        assert_eq!(warnings(MAP), (Some(14), Vec::new()));
+    }
+
+    #[test]
+    fn invalid_line_numbers() {
+        let text = MAP.replace(
+            "256,192,500,1,0,0:0:0:0:",
+            "abc\n256,192,500,1,0,0:0:0:0:\n256,x",
+        );
+        let (_, warnings) = warnings(&text);
+        assert_eq!(
+            warnings,
+            vec![(WARNING_INVALID_LINE, 11), (WARNING_INVALID_LINE, 13)]
+        );
+    }
+
+    #[test]
+    fn same_content_on_different_lines() {
+        let text = MAP.replace("[HitObjects]\n", "[HitObjects]\nabc\n") + "abc\n";
+        let (_, warnings) = warnings(&text);
+        assert_eq!(
+            warnings,
+            vec![(WARNING_INVALID_LINE, 10), (WARNING_INVALID_LINE, 13)]
+        );
+    }
+
+    #[test]
+    fn lines_under_unknown_section() {
+        let text = MAP.replace("[HitObjects]", "[Foo]\nbar\nbaz\n\n[HitObjects]");
+        let (_, warnings) = warnings(&text);
+        assert_eq!(warnings, vec![(WARNING_UNKNOWN_SECTION, 9)]);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let (version, warnings) = warnings(&MAP.replace("v14", "v15"));
+        assert_eq!(version, Some(15));
+        assert_eq!(warnings, vec![(WARNING_UNSUPPORTED_VERSION, 1)]);
+    }
+
+    #[test]
+    fn empty_map() {
+        let (version, warnings) = warnings("osu file format v14\n");
+        assert_eq!(version, Some(14));
+        assert_eq!(
+            warnings,
+            vec![
+                (WARNING_MISSING_TIMING_POINTS, 0),
+                (WARNING_NO_HIT_OBJECTS, 0)
+            ]
+        );
+    }
+}
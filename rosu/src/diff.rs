@@ -0,0 +1,526 @@
+use std::collections::BTreeMap;
+
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::hit_objects::{HitObject, HitObjectKind};
+use rosu_pp::Difficulty;
+
+use crate::beatmap::get_full_map;
+use crate::java::Result;
+use crate::objects::{KIND_CIRCLE, KIND_HOLD, KIND_SLIDER, KIND_SPINNER};
+use crate::pp::{get_map_and_attr, JniMapAttr};
+use crate::{vec_add_str, StatusFlag};
+
+const CHANGE_ADDED: u8 = 0;
+const CHANGE_REMOVED: u8 = 1;
+const CHANGE_MODIFIED: u8 = 2;
+
+/// 比较同一谱面的两个版本, 时间为谱面时间
+///
+/// ` [(none)u8 | (field size)i32 | (name | old | new) * size | (timing size)i32 | timing change * size | (object size)i32 | object change * size | (unchanged objects)i32 | (old stars, new stars)f64 * 2 | (gameplay changed)u8] `
+/// - field: 元数据与难度设置, 均转为字符串
+/// - timing change: `[(change)u8 | (red line)u8 | (time, old value, new value)f64 * 3]`, 红线的值为 beat length, 绿线为 slider velocity
+/// - object change: `[(change)u8 | (kind)u8 | (time)f64 | (old x, old y, new x, new y)f32 * 4]`
+///
+/// change 为 0 新增, 1 删除, 2 修改, 新增与删除时缺少的一侧为 0
+///
+/// 物件按开始时间与类型配对, 位置, 形状, 长度或新 combo 不同时视为修改, 不比较音效;
+/// gameplay changed 表示物件, 红绿线或难度设置有变化, 需要重新计算成绩
+pub fn diff_maps(
+    env: &JNIEnv,
+    old_map: &JByteArray,
+    new_map: &JByteArray,
+    attr: &JByteArray,
+) -> Result<Vec<u8>> {
+    let old = get_full_map(env, old_map)?;
+    let new = get_full_map(env, new_map)?;
+    let (old_converted, map_attr) = get_map_and_attr(env, old_map, attr)?;
+    let (new_converted, _) = get_map_and_attr(env, new_map, attr)?;
+
+    let (fields, settings_changed) = diff_fields(&old, &new);
+    let timing = diff_timing(&old, &new);
+    let (objects, unchanged) = diff_objects(&old.hit_objects, &new.hit_objects);
+
+    let old_stars = stars(&old_converted, &map_attr);
+    let new_stars = stars(&new_converted, &map_attr);
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::None.bits());
+    result.put_i32(fields.len() as i32);
+    for (name, old, new) in &fields {
+        vec_add_str(name, &mut result);
+        vec_add_str(old, &mut result);
+        vec_add_str(new, &mut result);
+    }
+    result.put_i32(timing.len() as i32);
+    for t in &timing {
+        result.put_u8(t.change);
+        result.put_u8(t.red as u8);
+        result.put_f64(t.time);
+        result.put_f64(t.old);
+        result.put_f64(t.new);
+    }
+    result.put_i32(objects.len() as i32);
+    for o in &objects {
+        result.put_u8(o.change);
+        result.put_u8(o.kind);
+        result.put_f64(o.time);
+        result.put_f32(o.old.0);
+        result.put_f32(o.old.1);
+        result.put_f32(o.new.0);
+        result.put_f32(o.new.1);
+    }
+    result.put_i32(unchanged as i32);
+    result.put_f64(old_stars);
+    result.put_f64(new_stars);
+    let gameplay_changed = settings_changed || !timing.is_empty() || !objects.is_empty();
+    result.put_u8(gameplay_changed as u8);
+    Ok(result)
+}
+
+struct TimingChange {
+    change: u8,
+    red: bool,
+    time: f64,
+    old: f64,
+    new: f64,
+}
+
+struct ObjectChange {
+    change: u8,
+    kind: u8,
+    time: f64,
+    old: (f32, f32),
+    new: (f32, f32),
+}
+
+fn stars(map: &rosu_pp::Beatmap, attr: &JniMapAttr) -> f64 {
+    let difficulty = Difficulty::new().mods(attr.mods);
+    let attributes = if attr.speed > 0.0 {
+        difficulty.clock_rate(attr.speed).calculate(map)
+    } else {
+        difficulty.calculate(map)
+    };
+    attributes.stars()
+}
+
+/// 返回有变化的字段, 以及难度设置是否有变化
+fn diff_fields(
+    old: &rosu_map::Beatmap,
+    new: &rosu_map::Beatmap,
+) -> (Vec<(&'static str, String, String)>, bool) {
+    type Field = (&'static str, fn(&rosu_map::Beatmap) -> String);
+    const METADATA: [Field; 13] = [
+        ("title", |m| m.title.clone()),
+        ("titleUnicode", |m| m.title_unicode.clone()),
+        ("artist", |m| m.artist.clone()),
+        ("artistUnicode", |m| m.artist_unicode.clone()),
+        ("creator", |m| m.creator.clone()),
+        ("version", |m| m.version.clone()),
+        ("source", |m| m.source.clone()),
+        ("tags", |m| m.tags.clone()),
+        ("beatmapId", |m| m.beatmap_id.to_string()),
+        ("beatmapSetId", |m| m.beatmap_set_id.to_string()),
+        ("audioFile", |m| m.audio_file.clone()),
+        ("backgroundFile", |m| m.background_file.clone()),
+        ("previewTime", |m| m.preview_time.to_string()),
+    ];
+    const SETTINGS: [Field; 8] = [
+        ("mode", |m| (m.mode as u8).to_string()),
+        ("hpDrainRate", |m| m.hp_drain_rate.to_string()),
+        ("circleSize", |m| m.circle_size.to_string()),
+        ("overallDifficulty", |m| m.overall_difficulty.to_string()),
+        ("approachRate", |m| m.approach_rate.to_string()),
+        ("sliderMultiplier", |m| m.slider_multiplier.to_string()),
+        ("sliderTickRate", |m| m.slider_tick_rate.to_string()),
+        ("stackLeniency", |m| m.stack_leniency.to_string()),
+    ];
+
+    let changed = |fields: &[Field]| -> Vec<_> {
+        fields
+            .iter()
+            .map(|(name, get)| (*name, get(old), get(new)))
+            .filter(|(_, old, new)| old != new)
+            .collect()
+    };
+
+    let mut fields = changed(&METADATA);
+    let settings = changed(&SETTINGS);
+    let settings_changed = !settings.is_empty();
+    fields.extend(settings);
+    (fields, settings_changed)
+}
+
+/// 红线比较 beat length, 绿线比较 slider velocity, 同一时间的点视为同一个
+fn diff_timing(old: &rosu_map::Beatmap, new: &rosu_map::Beatmap) -> Vec<TimingChange> {
+    let old_red: Vec<_> = old
+        .control_points
+        .timing_points
+        .iter()
+        .map(|p| (p.time, p.beat_len))
+        .collect();
+    let new_red: Vec<_> = new
+        .control_points
+        .timing_points
+        .iter()
+        .map(|p| (p.time, p.beat_len))
+        .collect();
+    let old_green: Vec<_> = old
+        .control_points
+        .difficulty_points
+        .iter()
+        .map(|p| (p.time, p.slider_velocity))
+        .collect();
+    let new_green: Vec<_> = new
+        .control_points
+        .difficulty_points
+        .iter()
+        .map(|p| (p.time, p.slider_velocity))
+        .collect();
+
+    let mut changes = Vec::new();
+    diff_points(&old_red, &new_red, true, &mut changes);
+    diff_points(&old_green, &new_green, false, &mut changes);
+    changes.sort_by(|a, b| a.time.total_cmp(&b.time));
+    changes
+}
+
+fn diff_points(old: &[(f64, f64)], new: &[(f64, f64)], red: bool, changes: &mut Vec<TimingChange>) {
+    let new_by_time: BTreeMap<i64, f64> = new.iter().map(|(t, v)| (time_key(*t), *v)).collect();
+    let old_by_time: BTreeMap<i64, f64> = old.iter().map(|(t, v)| (time_key(*t), *v)).collect();
+
+    for (time, value) in old {
+        match new_by_time.get(&time_key(*time)) {
+            Some(new_value) if (new_value - value).abs() > 1e-9 => changes.push(TimingChange {
+                change: CHANGE_MODIFIED,
+                red,
+                time: *time,
+                old: *value,
+                new: *new_value,
+            }),
+            Some(_) => {}
+            None => changes.push(TimingChange {
+                change: CHANGE_REMOVED,
+                red,
+                time: *time,
+                old: *value,
+                new: 0.0,
+            }),
+        }
+    }
+    for (time, value) in new {
+        if !old_by_time.contains_key(&time_key(*time)) {
+            changes.push(TimingChange {
+                change: CHANGE_ADDED,
+                red,
+                time: *time,
+                old: 0.0,
+                new: *value,
+            });
+        }
+    }
+}
+
+/// 返回变化的物件以及未变化的物件数量
+fn diff_objects(old: &[HitObject], new: &[HitObject]) -> (Vec<ObjectChange>, usize) {
+    let mut new_by_time: BTreeMap<i64, Vec<usize>> = BTreeMap::new();
+    for (i, h) in new.iter().enumerate() {
+        new_by_time
+            .entry(time_key(h.start_time))
+            .or_default()
+            .push(i);
+    }
+
+    let mut matched = vec![false; new.len()];
+    let mut changes = Vec::new();
+    let mut unchanged = 0;
+
+    // 同一时间可能有多个物件 (mania), 优先配对完全相同的
+    let mut pending = Vec::new();
+    for h in old {
+        let candidates = new_by_time.get(&time_key(h.start_time));
+        let same = candidates.and_then(|c| {
+            c.iter()
+                .copied()
+                .find(|&i| !matched[i] && same_object(h, &new[i]))
+        });
+        match same {
+            Some(i) => {
+                matched[i] = true;
+                unchanged += 1;
+            }
+            None => pending.push(h),
+        }
+    }
+
+    for h in pending {
+        let kind = object_kind(h);
+        let candidate = new_by_time.get(&time_key(h.start_time)).and_then(|c| {
+            c.iter()
+                .copied()
+                .find(|&i| !matched[i] && object_kind(&new[i]) == kind)
+        });
+        match candidate {
+            Some(i) => {
+                matched[i] = true;
+                changes.push(ObjectChange {
+                    change: CHANGE_MODIFIED,
+                    kind,
+                    time: h.start_time,
+                    old: object_pos(h),
+                    new: object_pos(&new[i]),
+                });
+            }
+            None => changes.push(ObjectChange {
+                change: CHANGE_REMOVED,
+                kind,
+                time: h.start_time,
+                old: object_pos(h),
+                new: (0.0, 0.0),
+            }),
+        }
+    }
+
+    for (h, _) in new.iter().zip(matched).filter(|(_, matched)| !matched) {
+        changes.push(ObjectChange {
+            change: CHANGE_ADDED,
+            kind: object_kind(h),
+            time: h.start_time,
+            old: (0.0, 0.0),
+            new: object_pos(h),
+        });
+    }
+
+    changes.sort_by(|a, b| a.time.total_cmp(&b.time));
+    (changes, unchanged)
+}
+
+/// 谱面中的时间为整数毫秒
+fn time_key(time: f64) -> i64 {
+    time.round() as i64
+}
+
+fn object_kind(h: &HitObject) -> u8 {
+    match h.kind {
+        HitObjectKind::Circle(_) => KIND_CIRCLE,
+        HitObjectKind::Slider(_) => KIND_SLIDER,
+        HitObjectKind::Spinner(_) => KIND_SPINNER,
+        HitObjectKind::Hold(_) => KIND_HOLD,
+    }
+}
+
+fn object_pos(h: &HitObject) -> (f32, f32) {
+    match h.kind {
+        HitObjectKind::Circle(ref c) => (c.pos.x, c.pos.y),
+        HitObjectKind::Slider(ref s) => (s.pos.x, s.pos.y),
+        HitObjectKind::Spinner(ref s) => (s.pos.x, s.pos.y),
+        HitObjectKind::Hold(ref h) => (h.pos_x, 0.0),
+    }
+}
+
+fn same_object(a: &HitObject, b: &HitObject) -> bool {
+    match (&a.kind, &b.kind) {
+        (HitObjectKind::Circle(a), HitObjectKind::Circle(b)) => {
+            a.pos == b.pos && a.new_combo == b.new_combo
+        }
+        (HitObjectKind::Slider(a), HitObjectKind::Slider(b)) => {
+            a.pos == b.pos
+                && a.new_combo == b.new_combo
+                && a.repeat_count == b.repeat_count
+                && a.path.control_points() == b.path.control_points()
+                && a.path.expected_dist() == b.path.expected_dist()
+        }
+        (HitObjectKind::Spinner(a), HitObjectKind::Spinner(b)) => a.duration == b.duration,
+        (HitObjectKind::Hold(a), HitObjectKind::Hold(b)) => {
+            a.pos_x == b.pos_x && a.duration == b.duration
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rosu_map::Beatmap;
+
+    use super::*;
+
+    const OLD: &str = "osu file format v14
+
+[General]
+Mode: 0
+
+[Metadata]
+Title:Song
+Version:Hard
+
+[Difficulty]
+OverallDifficulty:7
+ApproachRate:8
+SliderMultiplier:1.4
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+1000,-100,4,2,0,100,0,0
+3000,-50,4,2,0,100,0,0
+
+[HitObjects]
+100,100,1000,1,0,0:0:0:0:
+200,100,1500,2,0,L|300:100,1,100
+300,100,2000,1,0,0:0:0:0:
+400,100,2500,1,0,0:0:0:0:
+";
+
+    fn map(text: &str) -> Beatmap {
+        Beatmap::from_bytes(text.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn metadata_and_settings() {
+        let old = map(OLD);
+        let (fields, settings_changed) = diff_fields(&old, &old);
+        assert!(fields.is_empty());
+        assert!(!settings_changed);
+
+        let new = map(&OLD.replace("Version:Hard", "Version:Insane"));
+        let (fields, settings_changed) = diff_fields(&old, &new);
+        assert_eq!(
+            fields,
+            [("version", "Hard".to_owned(), "Insane".to_owned())]
+        );
+        assert!(!settings_changed);
+
+        let new = map(&OLD.replace("ApproachRate:8", "ApproachRate:9"));
+        let (fields, settings_changed) = diff_fields(&old, &new);
+        assert_eq!(fields, [("approachRate", "8".to_owned(), "9".to_owned())]);
+        assert!(settings_changed);
+    }
+
+    #[test]
+    fn timing_changes() {
+        let old = map(OLD);
+        let new = map(&OLD
+            .replace("0,500,4", "0,400,4")
+            .replace("3000,-50,4,2,0,100,0,0", "4000,-50,4,2,0,100,0,0"));
+        let changes: Vec<_> = diff_timing(&old, &new)
+            .iter()
+            .map(|t| (t.change, t.red, t.time, t.old, t.new))
+            .collect();
+
+        assert_eq!(
+            changes,
+            [
+                (CHANGE_MODIFIED, true, 0.0, 500.0, 400.0),
+                (CHANGE_REMOVED, false, 3000.0, 2.0, 0.0),
+                (CHANGE_ADDED, false, 4000.0, 0.0, 2.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn object_changes() {
+        let old = map(OLD);
+        let new = map(&OLD
+            .replace("300,100,2000,1", "320,100,2000,1")
+            .replace("400,100,2500,1,0,0:0:0:0:\n", "400,100,3000,1,0,0:0:0:0:\n"));
+        let (changes, unchanged) = diff_objects(&old.hit_objects, &new.hit_objects);
+        let changes: Vec<_> = changes
+            .iter()
+            .map(|o| (o.change, o.kind, o.time, o.old, o.new))
+            .collect();
+
+        assert_eq!(unchanged, 2);
+        assert_eq!(
+            changes,
+            [
+                (
+                    CHANGE_MODIFIED,
+                    KIND_CIRCLE,
+                    2000.0,
+                    (300.0, 100.0),
+                    (320.0, 100.0)
+                ),
+                (
+                    CHANGE_REMOVED,
+                    KIND_CIRCLE,
+                    2500.0,
+                    (400.0, 100.0),
+                    (0.0, 0.0)
+                ),
+                (
+                    CHANGE_ADDED,
+                    KIND_CIRCLE,
+                    3000.0,
+                    (0.0, 0.0),
+                    (400.0, 100.0)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn slider_shape_is_modified() {
+        let old = map(OLD);
+        let new = map(&OLD.replace("L|300:100,1,100", "L|300:150,1,100"));
+        let (changes, unchanged) = diff_objects(&old.hit_objects, &new.hit_objects);
+
+        assert_eq!(unchanged, 3);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].change, CHANGE_MODIFIED);
+        assert_eq!(changes[0].kind, KIND_SLIDER);
+    }
+
+    /// mania 同一时间的多个物件优先配对完全相同的
+    #[test]
+    fn same_time_objects() {
+        let text = "osu file format v14
+
+[General]
+Mode: 3
+
+[Difficulty]
+CircleSize:4
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+64,192,1000,1,0,0:0:0:0:
+192,192,1000,1,0,0:0:0:0:
+320,192,1000,128,0,1500:0:0:0:0:
+";
+        let old = map(text);
+        let new = map(&text.replace("64,192,1000,1", "448,192,1000,1"));
+        let (changes, unchanged) = diff_objects(&old.hit_objects, &new.hit_objects);
+
+        assert_eq!(unchanged, 2);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].change, CHANGE_MODIFIED);
+        assert_eq!(changes[0].old, (64.0, 192.0));
+        assert_eq!(changes[0].new, (448.0, 192.0));
+    }
+
+    #[test]
+    fn stars_with_mods() {
+        let map = rosu_pp::Beatmap::from_bytes(OLD.as_bytes()).unwrap();
+        let nomod = stars(&map, &JniMapAttr::default());
+        let dt = stars(
+            &map,
+            &JniMapAttr {
+                mods: 1 << 6,
+                ..Default::default()
+            },
+        );
+        let rate = stars(
+            &map,
+            &JniMapAttr {
+                speed: 1.5,
+                ..Default::default()
+            },
+        );
+
+        assert!(nomod > 0.0);
+        assert!(dt > nomod);
+        assert_eq!(dt, rate);
+    }
+}
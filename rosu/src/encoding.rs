@@ -0,0 +1,220 @@
+use std::borrow::Cow;
+
+use bytes::BufMut;
+use encoding_rs::{Encoding, GBK, SHIFT_JIS, UTF_16BE, UTF_16LE, UTF_8};
+use jni::objects::{JByteArray, JString};
+use jni::JNIEnv;
+
+use crate::java::{Error, Result};
+use crate::{vec_add_str, StatusFlag};
+
+/// 将谱面转换为 UTF-8, 并返回使用的编码
+///
+/// ` [(none)u8 | encoding | (map)u8 * n] `
+///
+/// `encoding` 为空时自动识别, 否则使用指定的编码 (如 `shift_jis`, `gbk`, `utf-16le`);
+/// 返回的谱面可以直接传给其他方法
+pub fn decode_map(env: &mut JNIEnv, local_map: &JByteArray, encoding: &JString) -> Result<Vec<u8>> {
+    let map_bytes = env.convert_byte_array(local_map)?;
+    let label: String = env.get_string(encoding)?.into();
+    let encoding = if label.is_empty() {
+        detect_encoding(&map_bytes)
+    } else {
+        Encoding::for_label(label.as_bytes())
+            .ok_or_else(|| Error::from(format!("unknown encoding \"{label}\"")))?
+    };
+    let (text, encoding) = decode_with(&map_bytes, encoding);
+
+    let mut result = Vec::<u8>::with_capacity(text.len() + 16);
+    result.put_u8(StatusFlag::None.bits());
+    vec_add_str(encoding.name(), &mut result);
+    result.extend_from_slice(&text);
+    Ok(result)
+}
+
+/// 从 java byte[] 读取谱面文件, 非 UTF-8 编码时会自动识别并转换
+pub(crate) fn read_map_bytes(env: &JNIEnv, local_map: &JByteArray) -> Result<Vec<u8>> {
+    let map_bytes = env.convert_byte_array(local_map)?;
+    let encoding = detect_encoding(&map_bytes);
+    match decode_with(&map_bytes, encoding).0 {
+        Cow::Borrowed(_) => Ok(map_bytes),
+        Cow::Owned(text) => Ok(text),
+    }
+}
+
+/// 优先使用 BOM; 否则大量的 0 字节视为 UTF-16, 合法的 UTF-8 直接使用, 其余在 Shift-JIS 与 GBK 中选择
+fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    // 0 字节也是合法的 UTF-8, 需要先检查 UTF-16
+    if let Some(encoding) = detect_utf16(bytes) {
+        return encoding;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+
+    let sjis_valid = !SHIFT_JIS.decode_without_bom_handling(bytes).1;
+    let gbk_valid = !GBK.decode_without_bom_handling(bytes).1;
+    match (sjis_valid, gbk_valid) {
+        (true, false) => SHIFT_JIS,
+        (false, true) => GBK,
+        _ if kana_pairs(bytes) >= gb2312_pairs(bytes) => SHIFT_JIS,
+        _ => GBK,
+    }
+}
+
+/// .osu 文件几乎都是 ASCII, UTF-16 时一半的字节为 0
+fn detect_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    if bytes.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let half = bytes.len() / 2;
+    let zeros = |offset: usize| {
+        bytes
+            .iter()
+            .skip(offset)
+            .step_by(2)
+            .filter(|b| **b == 0)
+            .count()
+    };
+    if zeros(1) * 2 > half {
+        Some(UTF_16LE)
+    } else if zeros(0) * 2 > half {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Shift-JIS 的平假名与片假名, 首字节为 0x82 或 0x83
+fn kana_pairs(bytes: &[u8]) -> usize {
+    double_byte_pairs(bytes)
+        .filter(|(lead, trail)| matches!(lead, 0x82 | 0x83) && *trail >= 0x40)
+        .count()
+}
+
+/// GB2312 的常用汉字区, 在 Shift-JIS 中大多会变成半角片假名
+fn gb2312_pairs(bytes: &[u8]) -> usize {
+    double_byte_pairs(bytes)
+        .filter(|(lead, trail)| (0xB0..=0xF7).contains(lead) && (0xA1..=0xFE).contains(trail))
+        .count()
+}
+
+fn double_byte_pairs(bytes: &[u8]) -> impl Iterator<Item = (u8, u8)> + '_ {
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        while i + 1 < bytes.len() {
+            if bytes[i] < 0x80 {
+                i += 1;
+                continue;
+            }
+            let pair = (bytes[i], bytes[i + 1]);
+            i += 2;
+            return Some(pair);
+        }
+        None
+    })
+}
+
+/// 转换为 UTF-8, 已经是合法的 UTF-8 时不复制; 有 BOM 时以 BOM 为准, 返回实际使用的编码
+fn decode_with<'a>(
+    bytes: &'a [u8],
+    encoding: &'static Encoding,
+) -> (Cow<'a, [u8]>, &'static Encoding) {
+    let (text, encoding, _) = encoding.decode(bytes);
+    let text = match text {
+        Cow::Borrowed(text) => Cow::Borrowed(text.as_bytes()),
+        Cow::Owned(text) => Cow::Owned(text.into_bytes()),
+    };
+    (text, encoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(title: &str) -> String {
+        format!("osu file format v14\n\n[Metadata]\nTitle:Title\nTitleUnicode:{title}\n")
+    }
+
+    fn utf16(text: &str, little_endian: bool) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|u| {
+                if little_endian {
+                    u.to_le_bytes()
+                } else {
+                    u.to_be_bytes()
+                }
+            })
+            .collect()
+    }
+
+    fn round_trip(bytes: &[u8]) -> (&'static Encoding, String) {
+        let encoding = detect_encoding(bytes);
+        let (text, encoding) = decode_with(bytes, encoding);
+        (encoding, String::from_utf8(text.into_owned()).unwrap())
+    }
+
+    #[test]
+    fn utf8() {
+        let text = map("千本桜");
+        assert_eq!(round_trip(text.as_bytes()), (UTF_8, text.clone()));
+        assert!(matches!(
+            decode_with(text.as_bytes(), UTF_8).0,
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn bom() {
+        let text = map("千本桜");
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend_from_slice(text.as_bytes());
+        assert_eq!(round_trip(&bytes), (UTF_8, text.clone()));
+
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(utf16(&text, true));
+        assert_eq!(round_trip(&bytes), (UTF_16LE, text));
+    }
+
+    #[test]
+    fn utf16_without_bom() {
+        let text = map("ひまわりの約束");
+        assert_eq!(round_trip(&utf16(&text, true)), (UTF_16LE, text.clone()));
+        assert_eq!(round_trip(&utf16(&text, false)), (UTF_16BE, text.clone()));
+        assert_eq!(detect_utf16(text.as_bytes()), None);
+    }
+
+    #[test]
+    fn shift_jis() {
+        for title in ["ひまわりの約束", "千本桜", "カゲロウデイズ"] {
+            let text = map(title);
+            let bytes = SHIFT_JIS.encode(&text).0;
+            assert_eq!(round_trip(&bytes), (SHIFT_JIS, text));
+        }
+    }
+
+    #[test]
+    fn gbk() {
+        for title in ["恋爱循环", "我们的歌", "千本樱"] {
+            let text = map(title);
+            let bytes = GBK.encode(&text).0;
+            assert_eq!(round_trip(&bytes), (GBK, text));
+        }
+    }
+
+    #[test]
+    fn double_byte_counts() {
+        let sjis = SHIFT_JIS.encode("あいうアイウ").0;
+        assert_eq!(kana_pairs(&sjis), 6);
+        assert_eq!(gb2312_pairs(&sjis), 0);
+
+        let gbk = GBK.encode("我们的歌").0;
+        assert_eq!(kana_pairs(&gbk), 0);
+        assert_eq!(gb2312_pairs(&gbk), 4);
+
+        assert_eq!(double_byte_pairs(b"ab\x82\xA0c\x83").count(), 1);
+    }
+}
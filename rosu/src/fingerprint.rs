@@ -0,0 +1,237 @@
+use bytes::{Buf, BufMut};
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::hit_objects::hit_samples::HitSoundType;
+use rosu_pp::model::mode::GameMode;
+
+use crate::encoding::read_map_bytes;
+use crate::java::{Error, Result};
+use crate::objects::{get_objects, ObjectInfo};
+use crate::pp::JniMapAttr;
+use crate::StatusFlag;
+
+/// 指纹格式变化时需要修改, 不同版本的指纹不能比较
+const FINGERPRINT_VERSION: u8 = 1;
+/// 每部分 MinHash 的长度
+const SIGNATURE_SIZE: usize = 64;
+const FINGERPRINT_LEN: usize = 1 + 4 + SIGNATURE_SIZE * 8 * 2;
+
+/// 节奏片段包含的物件间隔数, 与位置片段包含的移动数
+const RHYTHM_SHINGLE: usize = 4;
+const PATTERN_SHINGLE: usize = 3;
+/// 时间间隔的量化 (ms), 与位置的量化 (osu! 像素)
+const TIME_STEP: f64 = 5.0;
+const POS_STEP: f32 = 16.0;
+
+/// 由物件的节奏与位置计算谱面指纹, 与 offset, 元数据无关, 少量修改只会影响部分片段
+///
+/// ` [(none)u8 | (fingerprint)u8 * n] `
+///
+/// 指纹内容为 ` [(version)u8 | (object count)i32 | (rhythm minhash)i64 * 64 | (pattern minhash)i64 * 64] `
+/// - rhythm: 相邻物件的时间间隔与类型
+/// - pattern: osu 与 catch 为相邻物件的相对位置, mania 为所在列, taiko 为 don 或 kat
+pub fn fingerprint(env: &JNIEnv, local_map: &JByteArray) -> Result<Vec<u8>> {
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let (mode, objects) = get_objects(&map_bytes, &JniMapAttr::default())?;
+    if objects.len() <= RHYTHM_SHINGLE {
+        return Err(Error::from("not enough hit objects"));
+    }
+
+    let mut result = Vec::<u8>::with_capacity(FINGERPRINT_LEN + 1);
+    result.put_u8(StatusFlag::None.bits());
+    write_fingerprint(mode, &objects, &mut result);
+    Ok(result)
+}
+
+/// 比较两个 [`fingerprint`] 的结果
+///
+/// ` [(none)u8 | (rhythm, pattern, overall)f64 * 3] `
+///
+/// 前两项为对应片段集合的相似度估计 (0 ~ 1), overall 为两者平均后乘以物件数量之比
+pub fn compare_fingerprints(env: &JNIEnv, a: &JByteArray, b: &JByteArray) -> Result<Vec<u8>> {
+    let a = env.convert_byte_array(a)?;
+    let b = env.convert_byte_array(b)?;
+    let (a_count, a_rhythm, a_pattern) = read_fingerprint(&a)?;
+    let (b_count, b_rhythm, b_pattern) = read_fingerprint(&b)?;
+
+    let rhythm = similarity(&a_rhythm, &b_rhythm);
+    let pattern = similarity(&a_pattern, &b_pattern);
+    let count_ratio = f64::from(a_count.min(b_count)) / f64::from(a_count.max(b_count).max(1));
+    let overall = (rhythm + pattern) / 2.0 * count_ratio;
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::None.bits());
+    result.put_f64(rhythm);
+    result.put_f64(pattern);
+    result.put_f64(overall);
+    Ok(result)
+}
+
+type Signature = [u64; SIGNATURE_SIZE];
+
+fn write_fingerprint(mode: GameMode, objects: &[ObjectInfo], result: &mut Vec<u8>) {
+    let rhythm = min_hash(rhythm_shingles(objects));
+    let pattern = min_hash(pattern_shingles(mode, objects));
+
+    result.put_u8(FINGERPRINT_VERSION);
+    result.put_i32(objects.len() as i32);
+    for h in rhythm.iter().chain(pattern.iter()) {
+        result.put_u64(*h);
+    }
+}
+
+fn read_fingerprint(mut bytes: &[u8]) -> Result<(i32, Signature, Signature)> {
+    if bytes.len() != FINGERPRINT_LEN || bytes[0] != FINGERPRINT_VERSION {
+        return Err(Error::from("invalid fingerprint"));
+    }
+    bytes.advance(1);
+    let count = bytes.get_i32();
+    let mut rhythm = [0; SIGNATURE_SIZE];
+    let mut pattern = [0; SIGNATURE_SIZE];
+    rhythm.iter_mut().for_each(|h| *h = bytes.get_u64());
+    pattern.iter_mut().for_each(|h| *h = bytes.get_u64());
+    Ok((count, rhythm, pattern))
+}
+
+fn similarity(a: &Signature, b: &Signature) -> f64 {
+    let same = a.iter().zip(b).filter(|(a, b)| a == b).count();
+    same as f64 / SIGNATURE_SIZE as f64
+}
+
+fn rhythm_shingles(objects: &[ObjectInfo]) -> Vec<u64> {
+    let tokens: Vec<u64> = objects
+        .windows(2)
+        .map(|w| {
+            let interval = ((w[1].start_time - w[0].start_time) / TIME_STEP).round() as i64;
+            hash_tokens(&[interval as u64, u64::from(w[0].kind)])
+        })
+        .collect();
+    tokens.windows(RHYTHM_SHINGLE).map(hash_tokens).collect()
+}
+
+fn pattern_shingles(mode: GameMode, objects: &[ObjectInfo]) -> Vec<u64> {
+    let quantize = |v: f32| (v / POS_STEP).round() as i64 as u64;
+    let tokens: Vec<u64> = match mode {
+        GameMode::Osu | GameMode::Catch => objects
+            .windows(2)
+            .map(|w| {
+                let dx = quantize(w[1].pos.x - w[0].pos.x);
+                let dy = quantize(w[1].pos.y - w[0].pos.y);
+                hash_tokens(&[dx, dy])
+            })
+            .collect(),
+        GameMode::Mania => objects.iter().map(|h| quantize(h.pos.x)).collect(),
+        GameMode::Taiko => objects
+            .iter()
+            .map(|h| {
+                let kat = h.hit_sound & (HitSoundType::WHISTLE | HitSoundType::CLAP) != 0;
+                let finish = h.hit_sound & HitSoundType::FINISH != 0;
+                hash_tokens(&[u64::from(h.kind), kat as u64, finish as u64])
+            })
+            .collect(),
+    };
+    tokens.windows(PATTERN_SHINGLE).map(hash_tokens).collect()
+}
+
+/// 每个位置取片段在不同种子下哈希的最小值
+fn min_hash(shingles: Vec<u64>) -> Signature {
+    let mut signature = [u64::MAX; SIGNATURE_SIZE];
+    for shingle in shingles {
+        let mut seed = shingle;
+        for h in signature.iter_mut() {
+            seed = split_mix(seed);
+            *h = (*h).min(seed);
+        }
+    }
+    signature
+}
+
+/// 指纹需要长期保存, std 的哈希不保证在不同版本间一致
+fn hash_tokens(tokens: &[u64]) -> u64 {
+    tokens
+        .iter()
+        .fold(0xCBF2_9CE4_8422_2325, |h, t| split_mix(h ^ t))
+}
+
+fn split_mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use rosu_map::util::Pos;
+
+    use super::*;
+    use crate::objects::{KIND_CIRCLE, KIND_SLIDER};
+
+    fn objects(offset: f64, count: usize) -> Vec<ObjectInfo> {
+        (0..count)
+            .map(|i| ObjectInfo {
+                start_time: offset + i as f64 * 150.0 + (i % 3) as f64 * 25.0,
+                end_time: offset + i as f64 * 150.0,
+                pos: Pos::new((i * 37 % 512) as f32, (i * 53 % 384) as f32),
+                kind: if i % 5 == 0 { KIND_SLIDER } else { KIND_CIRCLE },
+                new_combo: false,
+                combo_index: 0,
+                index_in_combo: 0,
+                hit_sound: 0,
+            })
+            .collect()
+    }
+
+    fn encode(objects: &[ObjectInfo]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_fingerprint(GameMode::Osu, objects, &mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        let objects = objects(0.0, 100);
+        let bytes = encode(&objects);
+        assert_eq!(bytes.len(), FINGERPRINT_LEN);
+
+        let (count, rhythm, pattern) = read_fingerprint(&bytes).unwrap();
+        assert_eq!(count, 100);
+        assert_eq!(rhythm, min_hash(rhythm_shingles(&objects)));
+        assert_eq!(pattern, min_hash(pattern_shingles(GameMode::Osu, &objects)));
+    }
+
+    #[test]
+    fn offset_does_not_matter() {
+        let (_, a_rhythm, a_pattern) = read_fingerprint(&encode(&objects(0.0, 100))).unwrap();
+        let (_, b_rhythm, b_pattern) = read_fingerprint(&encode(&objects(1234.0, 100))).unwrap();
+        assert_eq!(similarity(&a_rhythm, &b_rhythm), 1.0);
+        assert_eq!(similarity(&a_pattern, &b_pattern), 1.0);
+    }
+
+    #[test]
+    fn small_edit_keeps_most_shingles() {
+        let original = objects(0.0, 200);
+        let mut edited = original.clone();
+        edited[100].pos = Pos::new(0.0, 0.0);
+
+        let (_, _, a) = read_fingerprint(&encode(&original)).unwrap();
+        let (_, _, b) = read_fingerprint(&encode(&edited)).unwrap();
+        let pattern = similarity(&a, &b);
+        assert!(pattern > 0.8 && pattern < 1.0, "{pattern}");
+    }
+
+    #[test]
+    fn min_hash_is_stable() {
+        // 指纹需要长期保存, 哈希结果不能改变
+        assert_eq!(split_mix(0), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(min_hash(Vec::new()), [u64::MAX; SIGNATURE_SIZE]);
+    }
+
+    #[test]
+    fn invalid_fingerprint() {
+        let mut bytes = encode(&objects(0.0, 100));
+        assert!(read_fingerprint(&bytes[1..]).is_err());
+        bytes[0] = FINGERPRINT_VERSION + 1;
+        assert!(read_fingerprint(&bytes).is_err());
+    }
+}
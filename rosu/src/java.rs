@@ -1,9 +1,23 @@
+use crate::analysis::*;
+use crate::beatmap::parse_metadata;
+use crate::check::{check_beatmap, check_mapset};
 use crate::db::*;
-use crate::pp::{calculate, calculate_pp, get_calculate};
+use crate::diagnostics::parse_diagnostics;
+use crate::diff::diff_maps;
+use crate::encoding::decode_map;
+use crate::fingerprint::{compare_fingerprints, fingerprint};
+use crate::json::{json_to_map, map_to_json};
+use crate::objects::list_hit_objects;
+use crate::osu::slider_geometry;
+use crate::pp::{
+    beatmap_attributes, beatmap_attributes_by_value, calculate, calculate_pp, get_calculate,
+};
+use crate::timing::analyze_timing;
+use crate::writer::{change_rate, cut_section, export_converted};
 use crate::{error_to_bytes, to_status};
 use error_chain::error_chain;
 use jni::objects::*;
-use jni::sys::{jint, jlong};
+use jni::sys::{jboolean, jdouble, jint, jlong};
 use jni::JNIEnv;
 use rosu_pp::GradualPerformance;
 
@@ -13,6 +27,7 @@ error_chain! {
         Jni(jni::errors::Error);
         JniOther(jni::errors::JniError);
         Db(osu_db::Error);
+        Json(serde_json::Error);
     }
     errors {
         LocalError(s: String)
@@ -87,6 +102,206 @@ jni_fn! {
     }
 }
 
+jni_fn! {
+    getBeatmapAttributes(env; local_map:JByteArray, attr:JByteArray) {
+        let result = beatmap_attributes(&env, &local_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    getBeatmapAttributesByValue(env; value:JByteArray) {
+        let result = beatmap_attributes_by_value(&env, &value)
+        jni_result!(env, result)
+    }
+}
+
+/**************************************************************************************************/
+jni_fn! {
+    parseMetadata(env; local_map:JByteArray) {
+        let result = parse_metadata(&env, &local_map)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    analyzeTiming(env; local_map:JByteArray, attr:JByteArray) {
+        let result = analyze_timing(&env, &local_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    listHitObjects(env; local_map:JByteArray, attr:JByteArray) {
+        let result = list_hit_objects(&env, &local_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    getSliderGeometry(env; local_map:JByteArray, attr:JByteArray) {
+        let result = slider_geometry(&env, &local_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+/**************************************************************************************************/
+jni_fn! {
+    analyzeDensity(env; local_map:JByteArray, attr:JByteArray, window:jdouble) {
+        let result = note_density(&env, &local_map, &attr, window)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    classifyPatterns(env; local_map:JByteArray, attr:JByteArray) {
+        let result = classify_patterns(&env, &local_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    getSpacingStatistics(env; local_map:JByteArray, attr:JByteArray) {
+        let result = spacing_statistics(&env, &local_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    analyzeSnap(env; local_map:JByteArray) {
+        let result = rhythm_snap(&env, &local_map)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    analyzeScrollSpeed(env; local_map:JByteArray, attr:JByteArray) {
+        let result = scroll_speed(&env, &local_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    findHardSections(env; local_map:JByteArray, attr:JByteArray, window:jdouble, top:jint, spike_factor:jdouble) {
+        let result = hard_sections(&env, &local_map, &attr, window, top, spike_factor)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    analyzeReading(env; local_map:JByteArray, attr:JByteArray) {
+        let result = reading_difficulty(&env, &local_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    analyzeMania(env; local_map:JByteArray, attr:JByteArray) {
+        let result = mania_statistics(&env, &local_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    analyzeTaiko(env; local_map:JByteArray, attr:JByteArray) {
+        let result = taiko_statistics(&env, &local_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    analyzeCatch(env; local_map:JByteArray, attr:JByteArray) {
+        let result = catch_statistics(&env, &local_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+/**************************************************************************************************/
+
+jni_fn! {
+    changeRate(env; local_map:JByteArray, rate:jdouble, compensate:jboolean) {
+        let result = change_rate(&env, &local_map, rate, compensate != 0)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    exportConverted(env; local_map:JByteArray, attr:JByteArray) {
+        let result = export_converted(&env, &local_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    cutSection(env; local_map:JByteArray, start:jdouble, end:jdouble, lead_in:jdouble) {
+        let result = cut_section(&env, &local_map, start, end, lead_in)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    mapToJson(env; local_map:JByteArray) {
+        let result = map_to_json(&env, &local_map)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    jsonToMap(env; local_json:JByteArray) {
+        let result = json_to_map(&env, &local_json)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    diffMaps(env; old_map:JByteArray, new_map:JByteArray, attr:JByteArray) {
+        let result = diff_maps(&env, &old_map, &new_map, &attr)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    getFingerprint(env; local_map:JByteArray) {
+        let result = fingerprint(&env, &local_map)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    compareFingerprints(env; a:JByteArray, b:JByteArray) {
+        let result = compare_fingerprints(&env, &a, &b)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    checkBeatmap(env; local_map:JByteArray) {
+        let result = check_beatmap(&env, &local_map)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    checkMapset(mut env; local_maps:JObjectArray) {
+        let result = check_mapset(&mut env, &local_maps)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    decodeMap(mut env; local_map:JByteArray, encoding:JString) {
+        let result = decode_map(&mut env, &local_map, &encoding)
+        jni_result!(env, result)
+    }
+}
+
+jni_fn! {
+    parseDiagnostics(env; local_map:JByteArray, strict:jboolean) {
+        let result = parse_diagnostics(&env, &local_map, strict != 0)
+        jni_result!(env, result)
+    }
+}
+
 /**************************************************************************************************/
 jni_fn! {
     createCollection(mut env; collection: JObject) {
@@ -0,0 +1,982 @@
+use std::num::NonZeroU32;
+
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::colors::{Color, CustomColor};
+use rosu_map::section::events::{BreakPeriod, EventType};
+use rosu_map::section::general::{CountdownType, GameMode};
+use rosu_map::section::hit_objects::hit_samples::{
+    HitSampleDefaultName, HitSampleInfo, HitSampleInfoName, SampleBank,
+};
+use rosu_map::section::hit_objects::{
+    HitObject, HitObjectCircle, HitObjectHold, HitObjectKind, HitObjectSlider, HitObjectSpinner,
+    PathControlPoint, PathType, SliderPath, SplineType,
+};
+use rosu_map::section::timing_points::{
+    ControlPoints, DifficultyPoint, EffectPoint, SamplePoint, TimeSignature, TimingPoint,
+};
+use rosu_map::section::Section;
+use rosu_map::util::{Pos, StrExt};
+use rosu_map::Beatmap;
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostics::parse_error;
+use crate::encoding::read_map_bytes;
+use crate::java::{Error, Result};
+use crate::writer::encode_map;
+use crate::StatusFlag;
+
+/// 把 `.osu` 文件转为 json
+///
+/// ` [(none)u8 | (json utf-8)u8 * n] `
+///
+/// 包含解析器读取的全部内容; 解析器丢弃的事件 (故事板, 视频等) 与分区 (如 `[Variables]`, `[Mania]`)
+/// 按原文逐行保存在 `events.raw` 与 `extraSections` 中, 写回时原样输出
+pub fn map_to_json(env: &JNIEnv, local_map: &JByteArray) -> Result<Vec<u8>> {
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let json = serde_json::to_vec(&to_json(&map_bytes)?)?;
+
+    let mut result = Vec::<u8>::with_capacity(json.len() + 1);
+    result.put_u8(StatusFlag::None.bits());
+    result.extend_from_slice(&json);
+    Ok(result)
+}
+
+/// 把 [`map_to_json`] 格式的 json 写回 `.osu` 文件, 结果同 [`encode_map`]
+///
+/// json 中缺少的分区使用默认值
+pub fn json_to_map(env: &JNIEnv, local_json: &JByteArray) -> Result<Vec<u8>> {
+    let json = env.convert_byte_array(local_json)?;
+    let json: JsonBeatmap = serde_json::from_slice(&json)?;
+    from_json(json)
+}
+
+fn to_json(map_bytes: &[u8]) -> Result<JsonBeatmap> {
+    let map = Beatmap::from_bytes(map_bytes).map_err(parse_error)?;
+    let mut json = JsonBeatmap::from(map);
+    (json.events.raw, json.extra_sections) = raw_lines(&String::from_utf8_lossy(map_bytes));
+    Ok(json)
+}
+
+fn from_json(mut json: JsonBeatmap) -> Result<Vec<u8>> {
+    let raw_events = std::mem::take(&mut json.events.raw);
+    let extra_sections = std::mem::take(&mut json.extra_sections);
+    let mut map = Beatmap::try_from(json)?;
+    let mut result = encode_map(&mut map)?;
+
+    // rosu-map 按第一个绿线写出 SampleSet, 改回 json 中的值
+    let at = find(&result, SAMPLE_SET_KEY)? + SAMPLE_SET_KEY.len();
+    let len = result[at..]
+        .iter()
+        .position(|b| *b == b'\n')
+        .unwrap_or(result.len() - at);
+    let sample_set = (map.default_sample_bank as i32).to_string();
+    result.splice(at..at + len, sample_set.into_bytes());
+
+    // 原样的事件接在背景与休息段之后, 即 [TimingPoints] 前的空行处
+    let mut events = Vec::new();
+    for line in &raw_events {
+        events.extend_from_slice(line.as_bytes());
+        events.push(b'\n');
+    }
+    let at = find(&result, TIMING_POINTS_HEADER)?;
+    result.splice(at..at, events);
+
+    for line in &extra_sections {
+        if is_section_header(line) {
+            result.push(b'\n');
+        }
+        result.extend_from_slice(line.as_bytes());
+        result.push(b'\n');
+    }
+    Ok(result)
+}
+
+const TIMING_POINTS_HEADER: &[u8] = b"\n[TimingPoints]\n";
+const SAMPLE_SET_KEY: &[u8] = b"\nSampleSet: ";
+
+/// 在写出的 `.osu` 中查找
+fn find(result: &[u8], pattern: &[u8]) -> Result<usize> {
+    result
+        .windows(pattern.len())
+        .position(|w| w == pattern)
+        .ok_or_else(|| Error::from("unexpected encoded beatmap"))
+}
+
+/// 解析器丢弃的行: `[Events]` 中背景与休息段以外的行 (包括注释),
+/// 以及解析器不读取的分区 (包括分区名), 空行不保留
+///
+/// 背景与休息段由 json 中的字段写出, 写回后再次转换得到相同的结果
+fn raw_lines(text: &str) -> (Vec<String>, Vec<String>) {
+    let mut events = Vec::new();
+    let mut extra_sections = Vec::new();
+    let mut section = None;
+    let mut discarded = false;
+
+    for line in text
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim_end())
+    {
+        if line.is_empty() {
+            continue;
+        }
+        if is_section_header(line) {
+            section = Section::try_from_line(line);
+            discarded = matches!(
+                section,
+                None | Some(Section::Variables | Section::CatchTheBeat | Section::Mania)
+            );
+        }
+
+        if discarded {
+            extra_sections.push(line.to_owned());
+        } else if section == Some(Section::Events) && !is_section_header(line) {
+            let event_type = line.trim_comment().split(',').next().map(str::parse);
+            if !matches!(
+                event_type,
+                Some(Ok(EventType::Background | EventType::Break))
+            ) {
+                events.push(line.to_owned());
+            }
+        }
+    }
+
+    (events, extra_sections)
+}
+
+fn is_section_header(line: &str) -> bool {
+    line.starts_with('[') && line.ends_with(']')
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct JsonBeatmap {
+    format_version: i32,
+    general: General,
+    editor: Editor,
+    metadata: Metadata,
+    difficulty: Difficulty,
+    events: Events,
+    timing_points: TimingPoints,
+    colours: Colours,
+    hit_objects: Vec<JsonHitObject>,
+    /// 解析器不读取的分区, 逐行保存
+    extra_sections: Vec<String>,
+}
+
+impl Default for JsonBeatmap {
+    fn default() -> Self {
+        Self::from(Beatmap::default())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct General {
+    audio_filename: String,
+    audio_lead_in: f64,
+    preview_time: i32,
+    /// 0 ~ 3, 同 `.osu` 中的 SampleSet
+    sample_set: i32,
+    sample_volume: i32,
+    stack_leniency: f32,
+    mode: u8,
+    letterbox_in_breaks: bool,
+    special_style: bool,
+    widescreen_storyboard: bool,
+    epilepsy_warning: bool,
+    samples_match_playback_rate: bool,
+    countdown: i32,
+    countdown_offset: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Editor {
+    bookmarks: Vec<i32>,
+    distance_spacing: f64,
+    beat_divisor: i32,
+    grid_size: i32,
+    timeline_zoom: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Metadata {
+    title: String,
+    title_unicode: String,
+    artist: String,
+    artist_unicode: String,
+    creator: String,
+    version: String,
+    source: String,
+    tags: String,
+    beatmap_id: i32,
+    beatmap_set_id: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Difficulty {
+    hp_drain_rate: f32,
+    circle_size: f32,
+    overall_difficulty: f32,
+    approach_rate: f32,
+    slider_multiplier: f64,
+    slider_tick_rate: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Events {
+    background: String,
+    breaks: Vec<Break>,
+    /// 背景与休息段以外的行, 如故事板与视频
+    #[serde(default)]
+    raw: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Break {
+    start_time: f64,
+    end_time: f64,
+}
+
+/// 与 `.osu` 不同, 红线与各类绿线属性分开保存
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimingPoints {
+    timing: Vec<JsonTimingPoint>,
+    difficulty: Vec<JsonDifficultyPoint>,
+    effect: Vec<JsonEffectPoint>,
+    sample: Vec<JsonSamplePoint>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonTimingPoint {
+    time: f64,
+    beat_len: f64,
+    meter: u32,
+    omit_first_bar_line: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonDifficultyPoint {
+    time: f64,
+    slider_velocity: f64,
+    generate_ticks: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonEffectPoint {
+    time: f64,
+    kiai: bool,
+    scroll_speed: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonSamplePoint {
+    time: f64,
+    sample_set: i32,
+    sample_volume: i32,
+    custom_sample_bank: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Colours {
+    /// `[r, g, b, a]`
+    combo: Vec<[u8; 4]>,
+    custom: Vec<JsonCustomColour>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonCustomColour {
+    name: String,
+    colour: [u8; 4],
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonHitObject {
+    time: f64,
+    #[serde(flatten)]
+    kind: JsonHitObjectKind,
+    samples: Vec<JsonSample>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum JsonHitObjectKind {
+    #[serde(rename_all = "camelCase")]
+    Circle {
+        x: f32,
+        y: f32,
+        new_combo: bool,
+        combo_offset: i32,
+    },
+    #[serde(rename_all = "camelCase")]
+    Slider {
+        x: f32,
+        y: f32,
+        new_combo: bool,
+        combo_offset: i32,
+        /// 相对于起点的坐标
+        control_points: Vec<JsonControlPoint>,
+        length: Option<f64>,
+        repeat_count: i32,
+        /// 缺省时按红绿线计算
+        velocity: Option<f64>,
+        node_samples: Vec<Vec<JsonSample>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Spinner {
+        x: f32,
+        y: f32,
+        duration: f64,
+        new_combo: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    Hold { x: f32, duration: f64 },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonControlPoint {
+    x: f32,
+    y: f32,
+    /// 同 `.osu` 中的曲线类型, 如 `B`, `B3`, `L`, `P`, `C`
+    #[serde(rename = "type")]
+    path_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonSample {
+    /// `hitnormal`, `hitwhistle`, `hitfinish`, `hitclap` 或自定义音效文件名
+    name: String,
+    #[serde(default)]
+    file: bool,
+    bank: i32,
+    bank_specified: bool,
+    custom_sample_bank: i32,
+    volume: i32,
+    is_layered: bool,
+}
+
+impl From<Beatmap> for JsonBeatmap {
+    fn from(map: Beatmap) -> Self {
+        let control_points = map.control_points;
+
+        Self {
+            format_version: map.format_version,
+            general: General {
+                audio_filename: map.audio_file,
+                audio_lead_in: map.audio_lead_in,
+                preview_time: map.preview_time,
+                sample_set: map.default_sample_bank as i32,
+                sample_volume: map.default_sample_volume,
+                stack_leniency: map.stack_leniency,
+                mode: map.mode as u8,
+                letterbox_in_breaks: map.letterbox_in_breaks,
+                special_style: map.special_style,
+                widescreen_storyboard: map.widescreen_storyboard,
+                epilepsy_warning: map.epilepsy_warning,
+                samples_match_playback_rate: map.samples_match_playback_rate,
+                countdown: map.countdown as i32,
+                countdown_offset: map.countdown_offset,
+            },
+            editor: Editor {
+                bookmarks: map.bookmarks,
+                distance_spacing: map.distance_spacing,
+                beat_divisor: map.beat_divisor,
+                grid_size: map.grid_size,
+                timeline_zoom: map.timeline_zoom,
+            },
+            metadata: Metadata {
+                title: map.title,
+                title_unicode: map.title_unicode,
+                artist: map.artist,
+                artist_unicode: map.artist_unicode,
+                creator: map.creator,
+                version: map.version,
+                source: map.source,
+                tags: map.tags,
+                beatmap_id: map.beatmap_id,
+                beatmap_set_id: map.beatmap_set_id,
+            },
+            difficulty: Difficulty {
+                hp_drain_rate: map.hp_drain_rate,
+                circle_size: map.circle_size,
+                overall_difficulty: map.overall_difficulty,
+                approach_rate: map.approach_rate,
+                slider_multiplier: map.slider_multiplier,
+                slider_tick_rate: map.slider_tick_rate,
+            },
+            events: Events {
+                background: map.background_file,
+                breaks: map
+                    .breaks
+                    .iter()
+                    .map(|b| Break {
+                        start_time: b.start_time,
+                        end_time: b.end_time,
+                    })
+                    .collect(),
+                raw: Vec::new(),
+            },
+            timing_points: TimingPoints::from(control_points),
+            colours: Colours {
+                combo: map.custom_combo_colors.iter().map(|c| c.0).collect(),
+                custom: map
+                    .custom_colors
+                    .into_iter()
+                    .map(|c| JsonCustomColour {
+                        name: c.name,
+                        colour: c.color.0,
+                    })
+                    .collect(),
+            },
+            hit_objects: map
+                .hit_objects
+                .into_iter()
+                .map(JsonHitObject::from)
+                .collect(),
+            extra_sections: Vec::new(),
+        }
+    }
+}
+
+impl From<ControlPoints> for TimingPoints {
+    fn from(points: ControlPoints) -> Self {
+        Self {
+            timing: points
+                .timing_points
+                .iter()
+                .map(|p| JsonTimingPoint {
+                    time: p.time,
+                    beat_len: p.beat_len,
+                    meter: p.time_signature.numerator.get(),
+                    omit_first_bar_line: p.omit_first_bar_line,
+                })
+                .collect(),
+            difficulty: points
+                .difficulty_points
+                .iter()
+                .map(|p| JsonDifficultyPoint {
+                    time: p.time,
+                    slider_velocity: p.slider_velocity,
+                    generate_ticks: p.generate_ticks,
+                })
+                .collect(),
+            effect: points
+                .effect_points
+                .iter()
+                .map(|p| JsonEffectPoint {
+                    time: p.time,
+                    kiai: p.kiai,
+                    scroll_speed: p.scroll_speed,
+                })
+                .collect(),
+            sample: points
+                .sample_points
+                .iter()
+                .map(|p| JsonSamplePoint {
+                    time: p.time,
+                    sample_set: p.sample_bank as i32,
+                    sample_volume: p.sample_volume,
+                    custom_sample_bank: p.custom_sample_bank,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<HitObject> for JsonHitObject {
+    fn from(h: HitObject) -> Self {
+        let kind = match h.kind {
+            HitObjectKind::Circle(c) => JsonHitObjectKind::Circle {
+                x: c.pos.x,
+                y: c.pos.y,
+                new_combo: c.new_combo,
+                combo_offset: c.combo_offset,
+            },
+            HitObjectKind::Slider(s) => JsonHitObjectKind::Slider {
+                x: s.pos.x,
+                y: s.pos.y,
+                new_combo: s.new_combo,
+                combo_offset: s.combo_offset,
+                control_points: s
+                    .path
+                    .control_points()
+                    .iter()
+                    .map(JsonControlPoint::from)
+                    .collect(),
+                length: s.path.expected_dist(),
+                repeat_count: s.repeat_count,
+                velocity: Some(s.velocity),
+                node_samples: s
+                    .node_samples
+                    .iter()
+                    .map(|samples| samples.iter().map(JsonSample::from).collect())
+                    .collect(),
+            },
+            HitObjectKind::Spinner(s) => JsonHitObjectKind::Spinner {
+                x: s.pos.x,
+                y: s.pos.y,
+                duration: s.duration,
+                new_combo: s.new_combo,
+            },
+            HitObjectKind::Hold(h) => JsonHitObjectKind::Hold {
+                x: h.pos_x,
+                duration: h.duration,
+            },
+        };
+
+        Self {
+            time: h.start_time,
+            kind,
+            samples: h.samples.iter().map(JsonSample::from).collect(),
+        }
+    }
+}
+
+impl From<&PathControlPoint> for JsonControlPoint {
+    fn from(point: &PathControlPoint) -> Self {
+        let path_type = point.path_type.map(|t| match t.kind {
+            SplineType::Catmull => "C".to_owned(),
+            SplineType::BSpline => match t.degree {
+                Some(degree) => format!("B{degree}"),
+                None => "B".to_owned(),
+            },
+            SplineType::Linear => "L".to_owned(),
+            SplineType::PerfectCurve => "P".to_owned(),
+        });
+
+        Self {
+            x: point.pos.x,
+            y: point.pos.y,
+            path_type,
+        }
+    }
+}
+
+impl From<&HitSampleInfo> for JsonSample {
+    fn from(sample: &HitSampleInfo) -> Self {
+        let (name, file) = match sample.name {
+            HitSampleInfoName::Default(name) => (name.to_lowercase_str().to_owned(), false),
+            HitSampleInfoName::File(ref name) => (name.clone(), true),
+        };
+
+        Self {
+            name,
+            file,
+            bank: sample.bank as i32,
+            bank_specified: sample.bank_specified,
+            custom_sample_bank: sample.custom_sample_bank,
+            volume: sample.volume,
+            is_layered: sample.is_layered,
+        }
+    }
+}
+
+impl TryFrom<JsonBeatmap> for Beatmap {
+    type Error = Error;
+
+    fn try_from(json: JsonBeatmap) -> Result<Self> {
+        let general = json.general;
+        let mut map = Beatmap {
+            format_version: json.format_version,
+            audio_file: general.audio_filename,
+            audio_lead_in: general.audio_lead_in,
+            preview_time: general.preview_time,
+            default_sample_bank: sample_bank(general.sample_set)?,
+            default_sample_volume: general.sample_volume,
+            stack_leniency: general.stack_leniency,
+            mode: match general.mode {
+                0..=3 => GameMode::from(general.mode),
+                _ => return Err(Error::from("invalid mode")),
+            },
+            letterbox_in_breaks: general.letterbox_in_breaks,
+            special_style: general.special_style,
+            widescreen_storyboard: general.widescreen_storyboard,
+            epilepsy_warning: general.epilepsy_warning,
+            samples_match_playback_rate: general.samples_match_playback_rate,
+            countdown: match general.countdown {
+                0 => CountdownType::None,
+                1 => CountdownType::Normal,
+                2 => CountdownType::HalfSpeed,
+                3 => CountdownType::DoubleSpeed,
+                _ => return Err(Error::from("invalid countdown type")),
+            },
+            countdown_offset: general.countdown_offset,
+            bookmarks: json.editor.bookmarks,
+            distance_spacing: json.editor.distance_spacing,
+            beat_divisor: json.editor.beat_divisor,
+            grid_size: json.editor.grid_size,
+            timeline_zoom: json.editor.timeline_zoom,
+            title: json.metadata.title,
+            title_unicode: json.metadata.title_unicode,
+            artist: json.metadata.artist,
+            artist_unicode: json.metadata.artist_unicode,
+            creator: json.metadata.creator,
+            version: json.metadata.version,
+            source: json.metadata.source,
+            tags: json.metadata.tags,
+            beatmap_id: json.metadata.beatmap_id,
+            beatmap_set_id: json.metadata.beatmap_set_id,
+            hp_drain_rate: json.difficulty.hp_drain_rate,
+            circle_size: json.difficulty.circle_size,
+            overall_difficulty: json.difficulty.overall_difficulty,
+            approach_rate: json.difficulty.approach_rate,
+            slider_multiplier: json.difficulty.slider_multiplier,
+            slider_tick_rate: json.difficulty.slider_tick_rate,
+            background_file: json.events.background,
+            breaks: json
+                .events
+                .breaks
+                .iter()
+                .map(|b| BreakPeriod {
+                    start_time: b.start_time,
+                    end_time: b.end_time,
+                })
+                .collect(),
+            control_points: ControlPoints::try_from(json.timing_points)?,
+            custom_combo_colors: json.colours.combo.into_iter().map(Color).collect(),
+            custom_colors: json
+                .colours
+                .custom
+                .into_iter()
+                .map(|c| CustomColor {
+                    name: c.name,
+                    color: Color(c.colour),
+                })
+                .collect(),
+            hit_objects: Vec::with_capacity(json.hit_objects.len()),
+        };
+
+        for h in json.hit_objects {
+            let object = to_hit_object(h, &map)?;
+            map.hit_objects.push(object);
+        }
+
+        Ok(map)
+    }
+}
+
+impl TryFrom<TimingPoints> for ControlPoints {
+    type Error = Error;
+
+    fn try_from(points: TimingPoints) -> Result<Self> {
+        let mut control_points = ControlPoints::default();
+
+        for p in points.timing {
+            let meter = i32::try_from(p.meter).unwrap_or(0);
+            let time_signature =
+                TimeSignature::new(meter).map_err(|_| Error::from("invalid meter"))?;
+            control_points.timing_points.push(TimingPoint {
+                time: p.time,
+                beat_len: p.beat_len,
+                omit_first_bar_line: p.omit_first_bar_line,
+                time_signature,
+            });
+        }
+        for p in points.difficulty {
+            control_points.difficulty_points.push(DifficultyPoint {
+                time: p.time,
+                slider_velocity: p.slider_velocity,
+                generate_ticks: p.generate_ticks,
+            });
+        }
+        for p in points.effect {
+            control_points.effect_points.push(EffectPoint {
+                time: p.time,
+                kiai: p.kiai,
+                scroll_speed: p.scroll_speed,
+            });
+        }
+        for p in points.sample {
+            control_points.sample_points.push(SamplePoint {
+                time: p.time,
+                sample_bank: sample_bank(p.sample_set)?,
+                sample_volume: p.sample_volume,
+                custom_sample_bank: p.custom_sample_bank,
+            });
+        }
+
+        control_points
+            .timing_points
+            .sort_by(|a, b| a.time.total_cmp(&b.time));
+        control_points
+            .difficulty_points
+            .sort_by(|a, b| a.time.total_cmp(&b.time));
+        control_points
+            .effect_points
+            .sort_by(|a, b| a.time.total_cmp(&b.time));
+        control_points
+            .sample_points
+            .sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        Ok(control_points)
+    }
+}
+
+/// 滑条的 velocity 需要用到已经读取的红绿线
+fn to_hit_object(h: JsonHitObject, map: &Beatmap) -> Result<HitObject> {
+    let kind = match h.kind {
+        JsonHitObjectKind::Circle {
+            x,
+            y,
+            new_combo,
+            combo_offset,
+        } => HitObjectKind::Circle(HitObjectCircle {
+            pos: Pos::new(x, y),
+            new_combo,
+            combo_offset,
+        }),
+        JsonHitObjectKind::Slider {
+            x,
+            y,
+            new_combo,
+            combo_offset,
+            control_points,
+            length,
+            repeat_count,
+            velocity,
+            node_samples,
+        } => {
+            if control_points.is_empty() {
+                return Err(Error::from("slider has no control points"));
+            }
+            let control_points = control_points
+                .into_iter()
+                .map(|p| PathControlPoint {
+                    pos: Pos::new(p.x, p.y),
+                    path_type: p.path_type.as_deref().map(PathType::new_from_str),
+                })
+                .collect();
+            let node_samples = node_samples
+                .into_iter()
+                .map(|samples| samples.into_iter().map(to_sample).collect())
+                .collect::<Result<_>>()?;
+
+            HitObjectKind::Slider(HitObjectSlider {
+                pos: Pos::new(x, y),
+                new_combo,
+                combo_offset,
+                path: SliderPath::new(control_points, length),
+                node_samples,
+                repeat_count,
+                velocity: velocity.unwrap_or_else(|| slider_velocity(map, h.time)),
+            })
+        }
+        JsonHitObjectKind::Spinner {
+            x,
+            y,
+            duration,
+            new_combo,
+        } => HitObjectKind::Spinner(HitObjectSpinner {
+            pos: Pos::new(x, y),
+            duration,
+            new_combo,
+        }),
+        JsonHitObjectKind::Hold { x, duration } => {
+            HitObjectKind::Hold(HitObjectHold { pos_x: x, duration })
+        }
+    };
+
+    Ok(HitObject {
+        start_time: h.time,
+        kind,
+        samples: h
+            .samples
+            .into_iter()
+            .map(to_sample)
+            .collect::<Result<_>>()?,
+    })
+}
+
+/// 与 rosu-map 读取时相同的计算方式
+fn slider_velocity(map: &Beatmap, time: f64) -> f64 {
+    let beat_len = map
+        .control_points
+        .timing_point_at(time)
+        .map_or(TimingPoint::DEFAULT_BEAT_LEN, |p| p.beat_len);
+    let slider_velocity = map
+        .control_points
+        .difficulty_point_at(time)
+        .map_or(DifficultyPoint::DEFAULT_SLIDER_VELOCITY, |p| {
+            p.slider_velocity
+        });
+
+    let max = match map.mode {
+        GameMode::Osu | GameMode::Catch => 10_000.0,
+        GameMode::Taiko | GameMode::Mania => 1000.0,
+    };
+    let bpm_multiplier = if slider_velocity > 0.0 {
+        (100.0 / slider_velocity).clamp(10.0, max) / 100.0
+    } else {
+        1.0
+    };
+
+    100.0 * map.slider_multiplier / (beat_len * bpm_multiplier)
+}
+
+fn to_sample(sample: JsonSample) -> Result<HitSampleInfo> {
+    let name = if sample.file {
+        HitSampleInfoName::File(sample.name)
+    } else {
+        HitSampleInfoName::Default(match sample.name.as_str() {
+            "hitnormal" => HitSampleDefaultName::Normal,
+            "hitwhistle" => HitSampleDefaultName::Whistle,
+            "hitfinish" => HitSampleDefaultName::Finish,
+            "hitclap" => HitSampleDefaultName::Clap,
+            _ => return Err(Error::from("invalid sample name")),
+        })
+    };
+
+    Ok(HitSampleInfo {
+        name,
+        bank: sample_bank(sample.bank)?,
+        suffix: u32::try_from(sample.custom_sample_bank)
+            .ok()
+            .filter(|bank| *bank >= 2)
+            .and_then(NonZeroU32::new),
+        volume: sample.volume,
+        custom_sample_bank: sample.custom_sample_bank,
+        bank_specified: sample.bank_specified,
+        is_layered: sample.is_layered,
+    })
+}
+
+fn sample_bank(bank: i32) -> Result<SampleBank> {
+    SampleBank::try_from(bank).map_err(|_| Error::from("invalid sample bank"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+PreviewTime: 1000
+StackLeniency: 0.7
+Mode: 0
+
+[Editor]
+Bookmarks: 1000,2000
+BeatDivisor: 4
+
+[Metadata]
+Title:Song
+Artist:Artist
+Creator:Mapper
+Version:Insane
+Tags:a b c
+
+[Difficulty]
+HPDrainRate:5
+CircleSize:4
+OverallDifficulty:8
+ApproachRate:9
+SliderMultiplier:1.4
+SliderTickRate:1
+
+[Events]
+//Background and Video events
+0,0,\"bg.jpg\",0,0
+Video,500,\"video.mp4\"
+//Break Periods
+2,3000,5000
+//Storyboard Layer 0 (Background)
+Sprite,Background,Centre,\"sb/star.png\",320,240
+ F,0,1000,2000,0,1
+ _M,0,1000,2000,320,240,100,100
+//Storyboard Sound Samples
+Sample,1000,0,\"hit.wav\",60
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+2000,-50,4,2,1,80,0,1
+
+[Colours]
+Combo1 : 255,0,0
+Combo2 : 0,255,0
+
+[HitObjects]
+100,100,1000,5,2,0:0:0:0:
+100,100,1500,2,0,B|200:150|300:100,2,200,2|0|8,1:0|0:0|2:0,0:0:0:0:
+256,192,5500,12,4,6500,0:0:0:0:
+
+[Variables]
+$bg=bg.jpg
+
+[Unknown]
+key: value
+";
+
+    fn to_value(map_bytes: &[u8]) -> serde_json::Value {
+        serde_json::to_value(to_json(map_bytes).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn raw_lines_kept() {
+        let json = to_json(MAP.as_bytes()).unwrap();
+
+        assert_eq!(json.events.background, "bg.jpg");
+        assert_eq!(json.events.breaks.len(), 1);
+        assert_eq!(
+            json.events.raw,
+            [
+                "//Background and Video events",
+                "Video,500,\"video.mp4\"",
+                "//Break Periods",
+                "//Storyboard Layer 0 (Background)",
+                "Sprite,Background,Centre,\"sb/star.png\",320,240",
+                " F,0,1000,2000,0,1",
+                " _M,0,1000,2000,320,240,100,100",
+                "//Storyboard Sound Samples",
+                "Sample,1000,0,\"hit.wav\",60",
+            ]
+        );
+        assert_eq!(
+            json.extra_sections,
+            ["[Variables]", "$bg=bg.jpg", "[Unknown]", "key: value"]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = to_value(MAP.as_bytes());
+        let map = from_json(serde_json::from_value(json.clone()).unwrap()).unwrap();
+        assert_eq!(map[0], StatusFlag::None.bits());
+
+        let text = String::from_utf8(map[1..].to_vec()).unwrap();
+        assert!(text.contains("2,3000,5000\n//Background and Video events\n"));
+        assert!(text.contains(" _M,0,1000,2000,320,240,100,100\n"));
+        assert!(text.ends_with("\n[Variables]\n$bg=bg.jpg\n\n[Unknown]\nkey: value\n"));
+
+        assert_eq!(to_value(&map[1..]), json);
+    }
+
+    #[test]
+    fn missing_sections_use_defaults() {
+        let json: JsonBeatmap = serde_json::from_str("{\"formatVersion\":14}").unwrap();
+        let map = from_json(json).unwrap();
+        let json = to_json(&map[1..]).unwrap();
+
+        assert_eq!(json.format_version, 14);
+        assert!(json.hit_objects.is_empty());
+        assert!(json.events.raw.is_empty());
+        assert!(json.extra_sections.is_empty());
+    }
+}
@@ -1,13 +1,27 @@
 use std::mem;
 
 use bytes::BufMut;
+use rosu_map::section::general::GameMode;
 
 use java::Result;
 
+mod analysis;
+mod beatmap;
+mod catch_convert;
+mod check;
 mod db;
+mod diagnostics;
+mod diff;
+mod encoding;
+mod fingerprint;
 pub mod java;
+mod json;
 pub mod macros;
+mod objects;
+mod osu;
 mod pp;
+mod timing;
+mod writer;
 bitflags::bitflags! {
     struct StatusFlag :u8 {
         const Error = 0b10000000u8;
@@ -19,6 +33,16 @@ bitflags::bitflags! {
     }
 }
 
+/// 结果头部的模式标记
+fn mode_flag(mode: GameMode) -> StatusFlag {
+    match mode {
+        GameMode::Osu => StatusFlag::Osu,
+        GameMode::Taiko => StatusFlag::Taiko,
+        GameMode::Catch => StatusFlag::Catch,
+        GameMode::Mania => StatusFlag::Mania,
+    }
+}
+
 fn error_to_bytes(str: &str) -> Vec<u8> {
     let mut result = Vec::new();
     result.put_u8(StatusFlag::Error.bits());
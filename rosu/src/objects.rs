@@ -0,0 +1,282 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::general::GameMode;
+use rosu_map::section::hit_objects::hit_samples::HitSoundType;
+use rosu_map::section::hit_objects::{CurveBuffers, HitObjectKind};
+use rosu_map::util::Pos;
+use rosu_pp::model::hit_object::HitObjectKind as PpHitObjectKind;
+
+use crate::diagnostics::parse_error;
+use crate::encoding::read_map_bytes;
+use crate::java::{Error, Result};
+use crate::mode_flag;
+use crate::pp::{get_map_attr, JniMapAttr};
+
+/// osu! 坐标系的高度, HR 以此上下翻转
+pub(crate) const PLAYFIELD_HEIGHT: f32 = 384.0;
+
+pub(crate) const KIND_CIRCLE: u8 = 0;
+pub(crate) const KIND_SLIDER: u8 = 1;
+pub(crate) const KIND_SPINNER: u8 = 2;
+pub(crate) const KIND_HOLD: u8 = 3;
+
+/// 导出用的物件, 原谱与转谱共用
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ObjectInfo {
+    pub start_time: f64,
+    pub end_time: f64,
+    pub pos: Pos,
+    pub kind: u8,
+    pub new_combo: bool,
+    pub combo_index: i32,
+    pub index_in_combo: i32,
+    pub hit_sound: u8,
+}
+
+/// 列出谱面的所有物件, 可以指定模式转谱, HR 时 osu! 模式会上下翻转, 时间已按倍速换算
+///
+/// ` [(mode)u8 | (size)i32 | object * size] `
+/// - object: `[(start, end)f64 * 2 | (x, y)f32 * 2 | (kind)u8 | (new combo)u8 | (combo index, index in combo)i32 * 2 | (hit sound)u8]`
+/// - kind: `0 circle, 1 slider, 2 spinner, 3 hold`
+///
+/// 转为 taiko/mania 的谱面没有连击信息
+pub fn list_hit_objects(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    attr: &JByteArray,
+) -> Result<Vec<u8>> {
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let attr = get_map_attr(env, attr)?;
+    let (mode, objects) = get_objects(&map_bytes, &attr)?;
+    let clock_rate = attr.clock_rate();
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(mode_flag(mode).bits());
+    result.put_i32(objects.len() as i32);
+    for h in objects {
+        result.put_f64(h.start_time / clock_rate);
+        result.put_f64(h.end_time / clock_rate);
+        result.put_f32(h.pos.x);
+        result.put_f32(h.pos.y);
+        result.put_u8(h.kind);
+        result.put_u8(h.new_combo as u8);
+        result.put_i32(h.combo_index);
+        result.put_i32(h.index_in_combo);
+        result.put_u8(h.hit_sound);
+    }
+    Ok(result)
+}
+
+/// 读取物件, 需要转谱时由 rosu-pp 完成转换, 未经倍速换算
+pub(crate) fn get_objects(
+    map_bytes: &[u8],
+    attr: &JniMapAttr,
+) -> Result<(GameMode, Vec<ObjectInfo>)> {
+    let mut map = rosu_map::Beatmap::from_bytes(map_bytes).map_err(parse_error)?;
+    let mode = attr.mode.unwrap_or(map.mode);
+    let mut objects = map_objects(&mut map);
+
+    let objects = match (map.mode, mode) {
+        (GameMode::Osu, GameMode::Taiko | GameMode::Mania) => {
+            converted_objects(map_bytes, mode, &objects)?
+        }
+        (from, to) if from == to || (from == GameMode::Osu && to == GameMode::Catch) => {
+            if mode == GameMode::Osu && attr.hr() {
+                objects
+                    .iter_mut()
+                    .for_each(|h| h.pos.y = PLAYFIELD_HEIGHT - h.pos.y);
+            }
+            objects
+        }
+        _ => return Err(Error::from("incompatible mode")),
+    };
+
+    Ok((mode, objects))
+}
+
+fn map_objects(map: &mut rosu_map::Beatmap) -> Vec<ObjectInfo> {
+    let mut bufs = CurveBuffers::default();
+    let mut combo_index = 0;
+    let mut index_in_combo = 0;
+
+    map.hit_objects
+        .iter_mut()
+        .map(|h| {
+            let end_time = h.end_time_with_bufs(&mut bufs);
+            let hit_sound = u8::from(HitSoundType::from(h.samples.as_slice()));
+            let (pos, kind) = match h.kind {
+                HitObjectKind::Circle(ref c) => (c.pos, KIND_CIRCLE),
+                HitObjectKind::Slider(ref s) => (s.pos, KIND_SLIDER),
+                HitObjectKind::Spinner(ref s) => (s.pos, KIND_SPINNER),
+                HitObjectKind::Hold(ref h) => (Pos::new(h.pos_x, 192.0), KIND_HOLD),
+            };
+            let new_combo = h.new_combo();
+            if new_combo {
+                combo_index += 1 + combo_offset(&h.kind);
+                index_in_combo = 0;
+            } else {
+                index_in_combo += 1;
+            }
+
+            ObjectInfo {
+                start_time: h.start_time,
+                end_time,
+                pos,
+                kind,
+                new_combo,
+                combo_index,
+                index_in_combo,
+                hit_sound,
+            }
+        })
+        .collect()
+}
+
+/// 新 combo 时跳过的颜色数, 转盘与长条没有偏移
+///
+/// 第一个物件与转盘后的物件在解析时已被标记为新 combo
+pub(crate) fn combo_offset(kind: &HitObjectKind) -> i32 {
+    match kind {
+        HitObjectKind::Circle(h) => h.combo_offset,
+        HitObjectKind::Slider(h) => h.combo_offset,
+        HitObjectKind::Spinner(_) | HitObjectKind::Hold(_) => 0,
+    }
+}
+
+/// 转谱后的物件, 鼓棒 (未被拆分的滑条) 时长与原滑条相同, 由原谱按开始时间查找
+///
+/// 两者都按开始时间排序, 只需一个游标同步前进
+fn converted_objects(
+    map_bytes: &[u8],
+    mode: GameMode,
+    original: &[ObjectInfo],
+) -> Result<Vec<ObjectInfo>> {
+    let mut map = rosu_pp::Beatmap::from_bytes(map_bytes).map_err(parse_error)?;
+    if !map.convert_in_place(mode).success() {
+        return Err(Error::from("incompatible mode"));
+    }
+
+    let mut sliders = original.iter().filter(|o| o.kind == KIND_SLIDER).peekable();
+    let objects = map
+        .hit_objects
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            let (kind, end_time) = match h.kind {
+                PpHitObjectKind::Circle => (KIND_CIRCLE, h.start_time),
+                PpHitObjectKind::Slider(_) => {
+                    while sliders.next_if(|o| o.start_time < h.start_time).is_some() {}
+                    let end_time = sliders
+                        .next_if(|o| o.start_time == h.start_time)
+                        .map_or(h.start_time, |o| o.end_time);
+                    (KIND_SLIDER, end_time)
+                }
+                PpHitObjectKind::Spinner(s) => (KIND_SPINNER, h.start_time + s.duration),
+                PpHitObjectKind::Hold(s) => (KIND_HOLD, h.start_time + s.duration),
+            };
+
+            ObjectInfo {
+                start_time: h.start_time,
+                end_time,
+                pos: h.pos,
+                kind,
+                new_combo: false,
+                combo_index: 0,
+                index_in_combo: 0,
+                hit_sound: map.hit_sounds.get(i).map_or(0, |s| u8::from(*s)),
+            }
+        })
+        .collect();
+
+    Ok(objects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "osu file format v14
+
+[General]
+Mode: 0
+
+[Difficulty]
+SliderMultiplier:1.4
+SliderTickRate:1
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+100,100,0,1,0,0:0:0:0:
+100,100,500,2,0,L|500:100,1,400
+256,192,2000,12,0,3000,0:0:0:0:
+100,300,3500,1,0,0:0:0:0:
+100,300,4000,21,0,0:0:0:0:
+150,300,4500,1,2,0:0:0:0:
+";
+
+    fn objects(mods: u32, mode: Option<GameMode>) -> (GameMode, Vec<ObjectInfo>) {
+        let attr = JniMapAttr {
+            mode,
+            mods,
+            ..Default::default()
+        };
+        get_objects(MAP.as_bytes(), &attr).unwrap()
+    }
+
+    #[test]
+    fn combo_indices() {
+        let (mode, objects) = objects(0, None);
+        assert_eq!(mode, GameMode::Osu);
+        let combos: Vec<_> = objects
+            .iter()
+            .map(|h| (h.kind, h.new_combo, h.combo_index, h.index_in_combo))
+            .collect();
+        // 第一个物件与转盘后的物件总是新的连击, 第五个物件跳过一个连击颜色
+        assert_eq!(
+            combos,
+            vec![
+                (KIND_CIRCLE, true, 1, 0),
+                (KIND_SLIDER, false, 1, 1),
+                (KIND_SPINNER, true, 2, 0),
+                (KIND_CIRCLE, true, 3, 0),
+                (KIND_CIRCLE, true, 5, 0),
+                (KIND_CIRCLE, false, 5, 1),
+            ]
+        );
+        assert_eq!(objects[5].hit_sound, 2);
+        assert_eq!(objects[2].end_time, 3000.0);
+        assert!((objects[1].end_time - (500.0 + 400.0 / 140.0 * 500.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn hard_rock_flip() {
+        let (_, objects) = objects(1 << 4, None);
+        assert_eq!(objects[0].pos, Pos::new(100.0, 284.0));
+        assert_eq!(objects[3].pos, Pos::new(100.0, 84.0));
+    }
+
+    #[test]
+    fn converted_drum_roll_keeps_slider_end() {
+        let (_, original) = objects(0, None);
+        let (mode, objects) = objects(0, Some(GameMode::Taiko));
+        assert_eq!(mode, GameMode::Taiko);
+        let kinds: Vec<_> = objects.iter().map(|h| h.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                KIND_CIRCLE,
+                KIND_SLIDER,
+                KIND_SPINNER,
+                KIND_CIRCLE,
+                KIND_CIRCLE,
+                KIND_CIRCLE
+            ]
+        );
+        assert_eq!(objects[1].end_time, original[1].end_time);
+        assert_eq!(objects[2].end_time, 3000.0);
+        assert!(objects.iter().all(|h| !h.new_combo && h.combo_index == 0));
+    }
+}
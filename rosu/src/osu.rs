@@ -0,0 +1,442 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::general::GameMode;
+use rosu_map::section::hit_objects::{
+    CurveBuffers, HitObjectKind, HitObjectSlider, SliderEvent, SliderEventType, SliderEventsIter,
+};
+use rosu_map::section::timing_points::DifficultyPoint;
+use rosu_map::util::Pos;
+use rosu_pp::model::beatmap::BeatmapAttributesBuilder;
+
+use crate::diagnostics::parse_error;
+use crate::encoding::read_map_bytes;
+use crate::java::{Error, Result};
+use crate::objects::{KIND_CIRCLE, KIND_SLIDER, KIND_SPINNER, PLAYFIELD_HEIGHT};
+use crate::pp::{get_map_attr, JniMapAttr};
+use crate::StatusFlag;
+
+const STACK_DISTANCE: f32 = 3.0;
+
+/// 经过 HR 翻转与堆叠处理的 osu! 物件, 时间未经倍速换算
+pub(crate) struct OsuObject {
+    pub start_time: f64,
+    pub end_time: f64,
+    /// 未堆叠的位置
+    pub pos: Pos,
+    pub stack_height: i32,
+    pub stack_offset: Pos,
+    pub kind: u8,
+    pub slider: Option<OsuSlider>,
+}
+
+/// [`get_osu_objects`] 的结果, 保留原谱面以便查询红线等信息
+pub(crate) struct OsuObjects {
+    pub map: rosu_map::Beatmap,
+    pub objects: Vec<OsuObject>,
+    /// 受 mods 影响的圆圈半径 (osu! 像素)
+    pub radius: f32,
+}
+
+pub(crate) struct OsuSlider {
+    pub repeats: i32,
+    /// 曲线的折线顶点, 相对于滑条头
+    pub path: Vec<Pos>,
+    /// 滑条尾判定点 (legacy last tick) 的位置, 相对于滑条头
+    pub tail: Pos,
+    /// 曲线终点的位置, 与折返次数无关, 相对于滑条头
+    pub path_end: Pos,
+    /// `(time, 相对于滑条头的位置)`
+    pub ticks: Vec<(f64, Pos)>,
+}
+
+impl OsuObject {
+    pub fn is_circle(&self) -> bool {
+        self.kind == KIND_CIRCLE
+    }
+
+    pub fn is_slider(&self) -> bool {
+        self.kind == KIND_SLIDER
+    }
+
+    pub fn is_spinner(&self) -> bool {
+        self.kind == KIND_SPINNER
+    }
+
+    fn end_pos(&self) -> Pos {
+        self.slider
+            .as_ref()
+            .map_or(self.pos, |slider| self.pos + slider.tail)
+    }
+
+    pub fn stacked_pos(&self) -> Pos {
+        self.pos + self.stack_offset
+    }
+
+    pub fn stacked_end_pos(&self) -> Pos {
+        self.end_pos() + self.stack_offset
+    }
+}
+
+/// osu! 物件的滑条曲线与堆叠结果, 时间已按倍速换算, 位置均已包含堆叠偏移
+///
+/// ` [(osu)u8 | (size)i32 | object * size] `
+/// - object: `[(start, end)f64 * 2 | (x, y)f32 * 2 | (stack height)i32 | (kind)u8 | slider]`
+/// - slider (仅 kind 为 1 时): `[(repeats)i32 | (path size)i32 | (x, y)f32 * 2 * path size | (tick size)i32 | [(time)f64 | (x, y)f32 * 2] * tick size]`
+pub fn slider_geometry(env: &JNIEnv, local_map: &JByteArray, attr: &JByteArray) -> Result<Vec<u8>> {
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let attr = get_map_attr(env, attr)?;
+    let OsuObjects { objects, .. } = get_osu_objects(&map_bytes, &attr)?;
+    let clock_rate = attr.clock_rate();
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::Osu.bits());
+    result.put_i32(objects.len() as i32);
+    for h in &objects {
+        let pos = h.stacked_pos();
+        result.put_f64(h.start_time / clock_rate);
+        result.put_f64(h.end_time / clock_rate);
+        result.put_f32(pos.x);
+        result.put_f32(pos.y);
+        result.put_i32(h.stack_height);
+        result.put_u8(h.kind);
+
+        if let Some(slider) = &h.slider {
+            result.put_i32(slider.repeats);
+            result.put_i32(slider.path.len() as i32);
+            for p in &slider.path {
+                result.put_f32(pos.x + p.x);
+                result.put_f32(pos.y + p.y);
+            }
+            result.put_i32(slider.ticks.len() as i32);
+            for (time, p) in &slider.ticks {
+                result.put_f64(time / clock_rate);
+                result.put_f32(pos.x + p.x);
+                result.put_f32(pos.y + p.y);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// 读取 osu! 物件, 与游戏相同地处理 HR 翻转以及堆叠 (堆叠与 ar, cs 相关)
+pub(crate) fn get_osu_objects(map_bytes: &[u8], attr: &JniMapAttr) -> Result<OsuObjects> {
+    let mut map = rosu_map::Beatmap::from_bytes(map_bytes).map_err(parse_error)?;
+    if map.mode != GameMode::Osu || attr.mode.is_some_and(|m| m != GameMode::Osu) {
+        return Err(Error::from("only osu! beatmap is supported"));
+    }
+
+    // 堆叠使用谱面时间, 不受倍速影响
+    let map_attr = BeatmapAttributesBuilder::new()
+        .mode(GameMode::Osu, false)
+        .ar(map.approach_rate, false)
+        .od(map.overall_difficulty, false)
+        .cs(map.circle_size, false)
+        .hp(map.hp_drain_rate, false)
+        .mods(attr.mods)
+        .clock_rate(1.0)
+        .build();
+    let hr = attr.hr();
+    let slider_settings = SliderSettings {
+        slider_multiplier: map.slider_multiplier,
+        slider_tick_rate: map.slider_tick_rate,
+        format_version: map.format_version,
+    };
+
+    let mut bufs = CurveBuffers::default();
+    let mut ticks_buf = Vec::new();
+    let mut objects: Vec<OsuObject> = Vec::with_capacity(map.hit_objects.len());
+
+    for h in map.hit_objects.iter_mut() {
+        let start_time = h.start_time;
+        let end_time = h.end_time_with_bufs(&mut bufs);
+        let (pos, kind, slider) = match h.kind {
+            HitObjectKind::Circle(ref c) => (c.pos, KIND_CIRCLE, None),
+            HitObjectKind::Slider(ref mut s) => {
+                let difficulty_point = map.control_points.difficulty_point_at(start_time).map_or(
+                    (
+                        DifficultyPoint::DEFAULT_SLIDER_VELOCITY,
+                        DifficultyPoint::DEFAULT_GENERATE_TICKS,
+                    ),
+                    |p| (p.slider_velocity, p.generate_ticks),
+                );
+                let slider = osu_slider(
+                    s,
+                    start_time,
+                    difficulty_point,
+                    &slider_settings,
+                    &mut bufs,
+                    &mut ticks_buf,
+                );
+                (s.pos, KIND_SLIDER, Some(slider))
+            }
+            // mania 的长条在 osu! 中不会出现, 按转盘处理
+            HitObjectKind::Spinner(ref s) => (s.pos, KIND_SPINNER, None),
+            HitObjectKind::Hold(ref h) => (Pos::new(h.pos_x, 192.0), KIND_SPINNER, None),
+        };
+
+        objects.push(OsuObject {
+            start_time,
+            end_time,
+            pos,
+            stack_height: 0,
+            stack_offset: Pos::default(),
+            kind,
+            slider,
+        });
+    }
+
+    if hr {
+        for h in objects.iter_mut() {
+            h.pos.y = PLAYFIELD_HEIGHT - h.pos.y;
+            if let Some(slider) = &mut h.slider {
+                slider.path.iter_mut().for_each(|p| p.y = -p.y);
+                slider.ticks.iter_mut().for_each(|(_, p)| p.y = -p.y);
+                slider.tail.y = -slider.tail.y;
+                slider.path_end.y = -slider.path_end.y;
+            }
+        }
+    }
+
+    let stack_threshold = map_attr.hit_windows.ar * f64::from(map.stack_leniency);
+    if map.format_version >= 6 {
+        stacking(&mut objects, stack_threshold);
+    } else {
+        old_stacking(&mut objects, stack_threshold);
+    }
+
+    let scale = (1.0 - 0.7 * (map_attr.cs as f32 - 5.0) / 5.0) / 2.0;
+    for h in objects.iter_mut() {
+        let offset = h.stack_height as f32 * scale * -6.4;
+        h.stack_offset = Pos::new(offset, offset);
+    }
+
+    Ok(OsuObjects {
+        map,
+        objects,
+        radius: 64.0 * scale,
+    })
+}
+
+struct SliderSettings {
+    slider_multiplier: f64,
+    slider_tick_rate: f64,
+    format_version: i32,
+}
+
+fn osu_slider(
+    slider: &mut HitObjectSlider,
+    start_time: f64,
+    (slider_velocity, generate_ticks): (f64, bool),
+    settings: &SliderSettings,
+    bufs: &mut CurveBuffers,
+    ticks_buf: &mut Vec<SliderEvent>,
+) -> OsuSlider {
+    let span_count = slider.span_count();
+    let velocity = slider.velocity;
+    let curve = slider.path.curve_with_bufs(bufs);
+    let dist = curve.dist();
+    let span_duration = dist / velocity;
+
+    let scoring_dist = 100.0 * settings.slider_multiplier * slider_velocity;
+    let tick_dist_multiplier = if settings.format_version < 8 {
+        slider_velocity.recip()
+    } else {
+        1.0
+    };
+    let tick_dist = if generate_ticks {
+        scoring_dist / settings.slider_tick_rate * tick_dist_multiplier
+    } else {
+        f64::INFINITY
+    };
+
+    let events = SliderEventsIter::new(
+        start_time,
+        span_duration,
+        velocity,
+        tick_dist,
+        dist,
+        span_count,
+        ticks_buf,
+    );
+
+    let mut ticks = Vec::new();
+    let mut tail = None;
+    for e in events {
+        match e.kind {
+            SliderEventType::Tick => ticks.push((e.time, curve.position_at(e.path_progress))),
+            SliderEventType::LastTick => tail = Some(curve.position_at(e.path_progress)),
+            _ => {}
+        }
+    }
+    let end_progress = if span_count % 2 == 0 { 0.0 } else { 1.0 };
+
+    OsuSlider {
+        repeats: slider.repeat_count,
+        path: curve.path().to_vec(),
+        tail: tail.unwrap_or_else(|| curve.position_at(end_progress)),
+        path_end: curve.position_at(1.0),
+        ticks,
+    }
+}
+
+/// osu! (format version >= 6) 的堆叠算法
+fn stacking(objects: &mut [OsuObject], stack_threshold: f64) {
+    let mut extended_start_idx = 0;
+
+    let Some(extended_end_idx) = objects.len().checked_sub(1) else {
+        return;
+    };
+
+    for i in (1..=extended_end_idx).rev() {
+        let mut n = i;
+        let mut obj_i_idx = i;
+
+        if objects[obj_i_idx].stack_height != 0 || objects[obj_i_idx].is_spinner() {
+            continue;
+        }
+
+        if objects[obj_i_idx].is_circle() {
+            while let Some(prev) = n.checked_sub(1) {
+                n = prev;
+
+                if objects[n].is_spinner() {
+                    continue;
+                }
+
+                if objects[obj_i_idx].start_time - objects[n].end_time > stack_threshold {
+                    break;
+                }
+
+                if n < extended_start_idx {
+                    objects[n].stack_height = 0;
+                    extended_start_idx = n;
+                }
+
+                // 在滑条尾下方的圆圈向右下方堆叠
+                if objects[n].is_slider()
+                    && objects[n].end_pos().distance(objects[obj_i_idx].pos) < STACK_DISTANCE
+                {
+                    let offset = objects[obj_i_idx].stack_height - objects[n].stack_height + 1;
+
+                    for j in n + 1..=i {
+                        if objects[n].end_pos().distance(objects[j].pos) < STACK_DISTANCE {
+                            objects[j].stack_height -= offset;
+                        }
+                    }
+
+                    break;
+                }
+
+                if objects[n].pos.distance(objects[obj_i_idx].pos) < STACK_DISTANCE {
+                    objects[n].stack_height = objects[obj_i_idx].stack_height + 1;
+                    obj_i_idx = n;
+                }
+            }
+        } else if objects[obj_i_idx].is_slider() {
+            while let Some(prev) = n.checked_sub(1) {
+                n = prev;
+
+                if objects[n].is_spinner() {
+                    continue;
+                }
+
+                if objects[obj_i_idx].start_time - objects[n].start_time > stack_threshold {
+                    break;
+                }
+
+                if objects[n].end_pos().distance(objects[obj_i_idx].pos) < STACK_DISTANCE {
+                    objects[n].stack_height = objects[obj_i_idx].stack_height + 1;
+                    obj_i_idx = n;
+                }
+            }
+        }
+    }
+}
+
+/// osu! (format version < 6) 的堆叠算法
+fn old_stacking(objects: &mut [OsuObject], stack_threshold: f64) {
+    for i in 0..objects.len() {
+        if objects[i].stack_height != 0 && !objects[i].is_slider() {
+            continue;
+        }
+
+        let mut start_time = objects[i].end_time;
+        // 与 rosu-pp 相同, 使用曲线终点而不是滑条尾
+        let pos2 = objects[i]
+            .slider
+            .as_ref()
+            .map_or(objects[i].pos, |slider| objects[i].pos + slider.path_end);
+        let mut slider_stack = 0;
+
+        for j in i + 1..objects.len() {
+            if objects[j].start_time - stack_threshold > start_time {
+                break;
+            }
+
+            if objects[j].pos.distance(objects[i].pos) < STACK_DISTANCE {
+                objects[i].stack_height += 1;
+                start_time = objects[j].end_time;
+            } else if objects[j].pos.distance(pos2) < STACK_DISTANCE {
+                slider_stack += 1;
+                objects[j].stack_height -= slider_stack;
+                start_time = objects[j].end_time;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 旧版本格式, 圆圈放在竖直滑条的曲线终点上
+    const MAP: &str = "osu file format v5
+
+[General]
+StackLeniency: 0.7
+Mode: 0
+
+[Difficulty]
+CircleSize:4
+OverallDifficulty:5
+ApproachRate:5
+SliderMultiplier:1.4
+SliderTickRate:1
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+100,100,0,2,0,L|100:200,1,100
+100,200,600,1,0,0:0:0:0:
+";
+
+    fn stack_heights(mods: u32) -> Vec<i32> {
+        let attr = JniMapAttr {
+            mods,
+            ..Default::default()
+        };
+        let OsuObjects { objects, .. } = get_osu_objects(MAP.as_bytes(), &attr).unwrap();
+        objects.iter().map(|h| h.stack_height).collect()
+    }
+
+    #[test]
+    fn old_stacking_on_path_end() {
+        assert_eq!(stack_heights(0), vec![0, -1]);
+    }
+
+    #[test]
+    fn old_stacking_on_path_end_with_hard_rock() {
+        let attr = JniMapAttr {
+            mods: 1 << 4,
+            ..Default::default()
+        };
+        let OsuObjects { objects, .. } = get_osu_objects(MAP.as_bytes(), &attr).unwrap();
+        let slider = objects[0].slider.as_ref().unwrap();
+        assert_eq!(objects[0].pos, Pos::new(100.0, 284.0));
+        assert_eq!(slider.path_end, Pos::new(0.0, -100.0));
+        assert_eq!(objects[1].pos, Pos::new(100.0, 184.0));
+        assert_eq!(stack_heights(1 << 4), vec![0, -1]);
+    }
+}
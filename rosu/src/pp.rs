@@ -1,12 +1,16 @@
 use bytes::{Buf, BufMut, Bytes};
 use jni::objects::JByteArray;
 use jni::JNIEnv;
+use rosu_mods::GameModsLegacy;
 use rosu_pp::any::{DifficultyAttributes, PerformanceAttributes, ScoreState};
+use rosu_pp::model::beatmap::BeatmapAttributesBuilder;
 use rosu_pp::model::mode::GameMode;
 use rosu_pp::{Beatmap, Difficulty, GradualPerformance, Performance};
 
+use crate::diagnostics::parse_error;
+use crate::encoding::read_map_bytes;
 use crate::java::{Error, Result};
-use crate::{to_ptr, to_status_use, StatusFlag};
+use crate::{mode_flag, to_ptr, to_status_use, StatusFlag};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct JniMapAttr {
@@ -22,6 +26,47 @@ pub struct JniScore {
     pub score: Option<ScoreState>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct JniMapValue {
+    pub attr: JniMapAttr,
+    pub ar: f32,
+    pub od: f32,
+    pub cs: f32,
+    pub hp: f32,
+}
+
+impl JniMapAttr {
+    /// 与 rosu-pp 相同地由 bits 解析 mods
+    fn legacy_mods(&self) -> GameModsLegacy {
+        GameModsLegacy::from_bits(self.mods)
+    }
+
+    /// 实际倍速, 优先使用 `speed`, 否则由 DT/NC/HT 决定
+    pub fn clock_rate(&self) -> f64 {
+        if self.speed > 0.0 {
+            self.speed
+        } else {
+            f64::from(self.legacy_mods().clock_rate())
+        }
+    }
+
+    pub fn hr(&self) -> bool {
+        self.legacy_mods().contains(GameModsLegacy::HardRock)
+    }
+
+    pub fn ez(&self) -> bool {
+        self.legacy_mods().contains(GameModsLegacy::Easy)
+    }
+
+    pub fn hd(&self) -> bool {
+        self.legacy_mods().contains(GameModsLegacy::Hidden)
+    }
+
+    pub fn fl(&self) -> bool {
+        self.legacy_mods().contains(GameModsLegacy::Flashlight)
+    }
+}
+
 impl JniScore {
     pub fn performance<'a>(self, attr: DifficultyAttributes) -> Performance<'a> {
         let max_combo = attr.max_combo();
@@ -111,6 +156,30 @@ impl From<&[u8]> for JniMapAttr {
     }
 }
 
+impl From<&[u8]> for JniMapValue {
+    fn from(value: &[u8]) -> Self {
+        let attr = JniMapAttr::from(value);
+        if value.len() < 53 {
+            return JniMapValue {
+                attr,
+                ar: 5.0,
+                od: 5.0,
+                cs: 5.0,
+                hp: 5.0,
+            };
+        }
+
+        let mut bytes = Bytes::copy_from_slice(&value[21..53]);
+        JniMapValue {
+            attr,
+            ar: bytes.get_f64() as f32,
+            od: bytes.get_f64() as f32,
+            cs: bytes.get_f64() as f32,
+            hp: bytes.get_f64() as f32,
+        }
+    }
+}
+
 impl From<&[u8]> for JniScore {
     fn from(value: &[u8]) -> Self {
         let length = value.len();
@@ -212,6 +281,47 @@ pub fn calculate_pp(env: &JNIEnv, ptr: i64, score: &JByteArray) -> Result<Vec<u8
     Ok(result)
 }
 
+/// 计算谱面在 mods 与倍速影响下的实际属性以及判定区间
+///
+/// ` [(mode)u8 | (ar, od, cs, hp)f64 * 4 | (clock rate)f64 | (preempt)f64 | (radius)f64 | (size)i32 | (hit window)f64 * size] `
+///
+/// 判定区间 (ms):
+/// - osu: `300, 100, 50`
+/// - taiko: `great, ok`
+/// - catch: 无
+/// - mania: `MAX, 300, 200, 100, 50`
+pub fn beatmap_attributes(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    attr: &JByteArray,
+) -> Result<Vec<u8>> {
+    let (map, attr) = get_map_and_attr(env, local_map, attr)?;
+    let builder = map.attributes();
+
+    let mut result = Vec::<u8>::new();
+    beatmap_attributes_to_bytes(builder, map.mode, &attr, map.is_convert, &mut result);
+    Ok(result)
+}
+
+/// 不读取谱面, 直接由 ar/od/cs/hp 数值计算实际属性
+///
+/// 返回值与 [`beatmap_attributes`] 相同
+pub fn beatmap_attributes_by_value(env: &JNIEnv, value: &JByteArray) -> Result<Vec<u8>> {
+    let value_bytes = env.convert_byte_array(value)?;
+    let value = JniMapValue::from(value_bytes.as_slice());
+    let mode = value.attr.mode.unwrap_or(GameMode::Osu);
+    let builder = BeatmapAttributesBuilder::new()
+        .mode(mode, false)
+        .ar(value.ar, false)
+        .od(value.od, false)
+        .cs(value.cs, false)
+        .hp(value.hp, false);
+
+    let mut result = Vec::<u8>::new();
+    beatmap_attributes_to_bytes(builder, mode, &value.attr, false, &mut result);
+    Ok(result)
+}
+
 /// 从 java byte[] 读取 谱面/成绩 数据
 fn get_map_and_score(
     env: &JNIEnv,
@@ -229,7 +339,7 @@ fn get_map_and_score(
 }
 
 /// 从 java byte[] 读取 谱面/Mods 数据
-fn get_map_and_attr(
+pub(crate) fn get_map_and_attr(
     env: &JNIEnv,
     local_map: &JByteArray,
     attr: &JByteArray,
@@ -245,12 +355,12 @@ fn get_map_and_attr(
 }
 
 fn get_map(env: &JNIEnv, local_map: &JByteArray) -> Result<Beatmap> {
-    let map_bytes = env.convert_byte_array(local_map)?;
-    let map = Beatmap::from_bytes(&map_bytes)?;
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let map = Beatmap::from_bytes(&map_bytes).map_err(parse_error)?;
     Ok(map)
 }
 
-fn get_map_attr(env: &JNIEnv, attr: &JByteArray) -> Result<JniMapAttr> {
+pub(crate) fn get_map_attr(env: &JNIEnv, attr: &JByteArray) -> Result<JniMapAttr> {
     let attr_bytes = env.convert_byte_array(attr)?;
     let attr = JniMapAttr::from(attr_bytes.as_slice());
     Ok(attr)
@@ -322,13 +432,94 @@ fn attr_to_bytes(attr: &PerformanceAttributes, result: &mut dyn BufMut) {
 }
 
 fn calculate_to_bytes(ptr: i64, mode: GameMode, mods: u32, result: &mut dyn BufMut) {
-    let head = match mode {
-        GameMode::Osu => StatusFlag::Osu,
-        GameMode::Taiko => StatusFlag::Taiko,
-        GameMode::Catch => StatusFlag::Catch,
-        GameMode::Mania => StatusFlag::Mania,
-    };
+    let head = mode_flag(mode);
     result.put_u8(head.bits());
     result.put_i32(mods as i32);
     result.put_i64(ptr);
 }
+
+fn beatmap_attributes_to_bytes(
+    builder: BeatmapAttributesBuilder,
+    mode: GameMode,
+    attr: &JniMapAttr,
+    is_convert: bool,
+    result: &mut dyn BufMut,
+) {
+    let mut builder = builder.mods(attr.mods);
+    if attr.speed > 0.0 {
+        builder = builder.clock_rate(attr.speed);
+    }
+    let attributes = builder.build();
+    // 不受倍速影响的属性, 用于换算各个判定区间
+    let base = builder.clock_rate(1.0).build();
+    let clock_rate = attributes.clock_rate;
+
+    let head = mode_flag(mode);
+    let hit_windows = match mode {
+        GameMode::Osu => vec![
+            difficulty_range(base.od, 80.0, 50.0, 20.0) / clock_rate,
+            difficulty_range(base.od, 140.0, 100.0, 60.0) / clock_rate,
+            difficulty_range(base.od, 200.0, 150.0, 100.0) / clock_rate,
+        ],
+        GameMode::Taiko => vec![
+            difficulty_range(base.od, 50.0, 35.0, 20.0) / clock_rate,
+            difficulty_range(base.od, 120.0, 80.0, 50.0) / clock_rate,
+        ],
+        GameMode::Catch => vec![],
+        GameMode::Mania => mania_hit_windows(base.od, attr, is_convert, clock_rate),
+    };
+    let radius = 64.0 * (1.0 - 0.7 * (attributes.cs - 5.0) / 5.0) / 2.0;
+
+    result.put_u8(head.bits());
+    result.put_f64(attributes.ar);
+    result.put_f64(attributes.od);
+    result.put_f64(attributes.cs);
+    result.put_f64(attributes.hp);
+    result.put_f64(clock_rate);
+    result.put_f64(attributes.hit_windows.ar);
+    result.put_f64(radius);
+    result.put_i32(hit_windows.len() as i32);
+    for window in hit_windows {
+        result.put_f64(window);
+    }
+}
+
+/// mania 的判定区间, 与 stable 一致: 转谱只区分 od 是否大于 4
+fn mania_hit_windows(od: f64, attr: &JniMapAttr, is_convert: bool, clock_rate: f64) -> Vec<f64> {
+    let windows = if !is_convert {
+        let od = od.clamp(0.0, 10.0);
+        [
+            16.0,
+            64.0 - 3.0 * od,
+            97.0 - 3.0 * od,
+            127.0 - 3.0 * od,
+            151.0 - 3.0 * od,
+        ]
+    } else if od.round_ties_even() > 4.0 {
+        [16.0, 34.0, 67.0, 97.0, 121.0]
+    } else {
+        [16.0, 47.0, 77.0, 97.0, 121.0]
+    };
+
+    windows
+        .into_iter()
+        .map(|mut window| {
+            if attr.hr() {
+                window /= 1.4;
+            } else if attr.ez() {
+                window *= 1.4;
+            }
+            ((window * clock_rate).floor() / clock_rate).ceil()
+        })
+        .collect()
+}
+
+pub(crate) fn difficulty_range(difficulty: f64, min: f64, mid: f64, max: f64) -> f64 {
+    if difficulty > 5.0 {
+        mid + (max - mid) * (difficulty - 5.0) / 5.0
+    } else if difficulty < 5.0 {
+        mid - (mid - min) * (5.0 - difficulty) / 5.0
+    } else {
+        mid
+    }
+}
@@ -0,0 +1,230 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::timing_points::{EffectPoint, TimingPoint};
+
+use crate::beatmap::{get_full_map, object_times};
+use crate::java::Result;
+use crate::pp::get_map_attr;
+use crate::StatusFlag;
+
+/// 分析谱面的 bpm 与长度, 所有时间与 bpm 均已按倍速换算
+///
+/// ` [(none)u8 | (bpm min, bpm max, bpm most common)f64 * 3 | (total length, drain length)f64 * 2 | (timing size)i32 | timing point * size | (difficulty size)i32 | difficulty point * size] `
+/// - timing point: `[(time, beat length, bpm)f64 * 3 | (time signature)i32 | (omit first bar line)u8]`
+/// - difficulty point: `[(time, slider velocity)f64 * 2]`
+///
+/// 之后依次为休息段, kiai 段, 以及除去休息段后的连续游玩段, 均为 ` [(size)i32 | (start, end)f64 * 2 * size] `
+pub fn analyze_timing(env: &JNIEnv, local_map: &JByteArray, attr: &JByteArray) -> Result<Vec<u8>> {
+    let mut map = get_full_map(env, local_map)?;
+    let clock_rate = get_map_attr(env, attr)?.clock_rate();
+    let times = object_times(&mut map);
+
+    let first_time = times.first().map_or(0.0, |(start, _)| *start);
+    let last_time = times.iter().map(|(_, end)| *end).fold(0.0, f64::max);
+    let total_length = last_time - first_time;
+    let break_time: f64 = map.breaks.iter().map(|b| b.duration()).sum();
+    let drain_length = (total_length - break_time).max(0.0);
+
+    let timing_points = &map.control_points.timing_points;
+    let beat_len_max = timing_points
+        .iter()
+        .map(|t| t.beat_len)
+        .reduce(f64::max)
+        .unwrap_or(TimingPoint::DEFAULT_BEAT_LEN);
+    let beat_len_min = timing_points
+        .iter()
+        .map(|t| t.beat_len)
+        .reduce(f64::min)
+        .unwrap_or(TimingPoint::DEFAULT_BEAT_LEN);
+    let beat_len_common = most_common_beat_len(timing_points, last_time);
+
+    let mut result = Vec::<u8>::new();
+    result.put_u8(StatusFlag::None.bits());
+    result.put_f64(60_000.0 / beat_len_max * clock_rate);
+    result.put_f64(60_000.0 / beat_len_min * clock_rate);
+    result.put_f64(60_000.0 / beat_len_common * clock_rate);
+    result.put_f64(total_length / clock_rate);
+    result.put_f64(drain_length / clock_rate);
+
+    result.put_i32(timing_points.len() as i32);
+    for point in timing_points {
+        result.put_f64(point.time / clock_rate);
+        result.put_f64(point.beat_len / clock_rate);
+        result.put_f64(60_000.0 / point.beat_len * clock_rate);
+        result.put_i32(point.time_signature.numerator.get() as i32);
+        result.put_u8(point.omit_first_bar_line as u8);
+    }
+
+    let difficulty_points = &map.control_points.difficulty_points;
+    result.put_i32(difficulty_points.len() as i32);
+    for point in difficulty_points {
+        result.put_f64(point.time / clock_rate);
+        result.put_f64(point.slider_velocity);
+    }
+
+    let breaks: Vec<(f64, f64)> = map
+        .breaks
+        .iter()
+        .map(|b| (b.start_time, b.end_time))
+        .collect();
+    let kiai = kiai_sections(&map.control_points.effect_points, last_time);
+    let drain = drain_segments(&breaks, first_time, last_time);
+    for sections in [&breaks, &kiai, &drain] {
+        sections_to_bytes(sections, clock_rate, &mut result);
+    }
+
+    Ok(result)
+}
+
+/// 由 effect point 的 kiai 标记得到 kiai 段, 未关闭的 kiai 持续到最后一个物件结束
+fn kiai_sections(effect_points: &[EffectPoint], last_time: f64) -> Vec<(f64, f64)> {
+    let mut sections = Vec::new();
+    let mut start = None;
+
+    for point in effect_points {
+        match (point.kiai, start) {
+            (true, None) => start = Some(point.time),
+            (false, Some(s)) => {
+                sections.push((s, point.time));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(s) = start {
+        if s < last_time {
+            sections.push((s, last_time));
+        }
+    }
+
+    sections
+}
+
+/// 第一个物件到最后一个物件之间, 被休息段分割出的游玩段
+fn drain_segments(breaks: &[(f64, f64)], first_time: f64, last_time: f64) -> Vec<(f64, f64)> {
+    let mut segments = Vec::new();
+    let mut start = first_time;
+
+    for &(break_start, break_end) in breaks {
+        if break_end <= start || break_start >= last_time {
+            continue;
+        }
+        if break_start > start {
+            segments.push((start, break_start));
+        }
+        start = break_end;
+    }
+
+    if last_time > start {
+        segments.push((start, last_time));
+    }
+
+    segments
+}
+
+fn sections_to_bytes(sections: &[(f64, f64)], clock_rate: f64, result: &mut dyn BufMut) {
+    result.put_i32(sections.len() as i32);
+    for (start, end) in sections {
+        result.put_f64(start / clock_rate);
+        result.put_f64(end / clock_rate);
+    }
+}
+
+/// 与 osu! 相同, 取持续时间最长的 beat length, 只统计最后一个物件之前的部分
+pub(crate) fn most_common_beat_len(timing_points: &[TimingPoint], last_time: f64) -> f64 {
+    let mut durations = Vec::<(f64, f64)>::new();
+
+    for (i, point) in timing_points.iter().enumerate() {
+        if point.time > last_time {
+            continue;
+        }
+        let current_time = if i == 0 { 0.0 } else { point.time };
+        let next_time = timing_points
+            .get(i + 1)
+            .map_or(last_time, |next| next.time.min(last_time));
+        let beat_len = (point.beat_len * 1000.0).round() / 1000.0;
+
+        match durations.iter_mut().find(|(len, _)| *len == beat_len) {
+            Some((_, duration)) => *duration += next_time - current_time,
+            None => durations.push((beat_len, next_time - current_time)),
+        }
+    }
+
+    durations
+        .into_iter()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(beat_len, _)| beat_len)
+        .or_else(|| timing_points.first().map(|t| t.beat_len))
+        .unwrap_or(TimingPoint::DEFAULT_BEAT_LEN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "osu file format v14
+
+[General]
+Mode: 0
+
+[Events]
+2,2000,6000
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+1000,-100,4,2,0,100,0,1
+3000,-100,4,2,0,100,0,0
+6000,400,3,2,0,100,1,1
+
+[HitObjects]
+256,192,0,1,0,0:0:0:0:
+256,192,1500,1,0,0:0:0:0:
+256,192,6500,1,0,0:0:0:0:
+256,192,8000,1,0,0:0:0:0:
+";
+
+    fn parse() -> (rosu_map::Beatmap, f64, f64) {
+        let mut map = rosu_map::Beatmap::from_bytes(MAP.as_bytes()).unwrap();
+        let times = object_times(&mut map);
+        (map, times[0].0, times[times.len() - 1].1)
+    }
+
+    #[test]
+    fn kiai_until_last_object() {
+        let (map, _, last_time) = parse();
+        assert_eq!(
+            kiai_sections(&map.control_points.effect_points, last_time),
+            vec![(1000.0, 3000.0), (6000.0, 8000.0)]
+        );
+    }
+
+    #[test]
+    fn drain_split_by_breaks() {
+        let (map, first_time, last_time) = parse();
+        let breaks: Vec<_> = map
+            .breaks
+            .iter()
+            .map(|b| (b.start_time, b.end_time))
+            .collect();
+        assert_eq!(breaks, vec![(2000.0, 6000.0)]);
+        assert_eq!(
+            drain_segments(&breaks, first_time, last_time),
+            vec![(0.0, 2000.0), (6000.0, 8000.0)]
+        );
+        // 完全在物件之外的休息段不影响游玩段
+        assert_eq!(
+            drain_segments(&[(9000.0, 9500.0)], first_time, last_time),
+            vec![(0.0, 8000.0)]
+        );
+    }
+
+    #[test]
+    fn most_common_beat_len_by_duration() {
+        let (map, _, last_time) = parse();
+        let timing_points = &map.control_points.timing_points;
+        assert_eq!(most_common_beat_len(timing_points, last_time), 500.0);
+        assert_eq!(most_common_beat_len(timing_points, 20_000.0), 400.0);
+    }
+}
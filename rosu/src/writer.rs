@@ -0,0 +1,659 @@
+use bytes::BufMut;
+use jni::objects::JByteArray;
+use jni::JNIEnv;
+use rosu_map::section::general::{CountdownType, GameMode};
+use rosu_map::section::hit_objects::hit_samples::{HitSampleInfo, HitSoundType, SampleBankInfo};
+use rosu_map::section::hit_objects::{
+    CurveBuffers, HitObject, HitObjectCircle, HitObjectHold, HitObjectKind, HitObjectSpinner,
+};
+use rosu_map::util::Pos;
+use rosu_map::Beatmap;
+use rosu_pp::model::hit_object::HitObjectKind as PpHitObjectKind;
+
+use crate::beatmap::get_full_map;
+use crate::diagnostics::parse_error;
+use crate::encoding::read_map_bytes;
+use crate::java::{Error, Result};
+use crate::objects::combo_offset;
+use crate::pp::{difficulty_range, get_map_attr};
+use crate::StatusFlag;
+
+/// 生成变速后的 `.osu` 文件, 物件, 红绿线, 休息段, 预览点与书签的时间均按 `rate` 缩放
+///
+/// ` [(none)u8 | (.osu)u8 * n] `
+///
+/// `compensate` 为 true 时调整 AR 与 OD, 使缩圈时间与判定区间与原谱开启同样倍速 (如 DT) 时相同 (超出 0 到 10 时截断);
+/// 难度名会加上倍速后缀, 音频文件需要另行处理
+pub fn change_rate(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    rate: f64,
+    compensate: bool,
+) -> Result<Vec<u8>> {
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err(Error::from("rate must be positive"));
+    }
+
+    let mut map = get_full_map(env, local_map)?;
+    scale_map(&mut map, rate);
+    if compensate {
+        compensate_difficulty(&mut map, rate);
+    }
+    map.version = format!("{} ({rate}x)", map.version);
+
+    encode_map(&mut map)
+}
+
+/// 把转谱结果写为 `.osu` 文件, `attr` 需要指定模式, 结果同 [`change_rate`]
+///
+/// - taiko: 拆分后的滑条写为音符, 保留鼓棒与转盘
+/// - catch: 物件不变, 只修改模式
+/// - mania: 按转谱的列写出音符与长条, CS 为键数, 音效取自原谱
+///
+/// 难度名会加上模式 (mania 为键数) 后缀
+pub fn export_converted(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    attr: &JByteArray,
+) -> Result<Vec<u8>> {
+    let map_bytes = read_map_bytes(env, local_map)?;
+    let mode = get_map_attr(env, attr)?
+        .mode
+        .ok_or_else(|| Error::from("mode is required"))?;
+    let mut map = convert_map(&map_bytes, mode)?;
+    encode_map(&mut map)
+}
+
+fn convert_map(map_bytes: &[u8], mode: GameMode) -> Result<Beatmap> {
+    let mut map = Beatmap::from_bytes(map_bytes).map_err(parse_error)?;
+    if map.mode == mode {
+        return Ok(map);
+    }
+
+    let mut converted = rosu_pp::Beatmap::from_bytes(map_bytes).map_err(parse_error)?;
+    if !converted.convert_in_place(mode).success() {
+        return Err(Error::from("incompatible mode"));
+    }
+
+    let suffix = match mode {
+        GameMode::Osu => "osu!".to_owned(),
+        GameMode::Taiko => "Taiko".to_owned(),
+        GameMode::Catch => "Catch".to_owned(),
+        GameMode::Mania => format!("{}K", converted.cs),
+    };
+    map.version = format!("{} ({suffix})", map.version);
+    map.mode = mode;
+
+    match mode {
+        GameMode::Taiko => map.hit_objects = taiko_objects(&map.hit_objects, &converted),
+        GameMode::Mania => {
+            map.circle_size = converted.cs;
+            map.hit_objects = mania_objects(&mut map.hit_objects, &converted);
+        }
+        _ => {}
+    }
+
+    Ok(map)
+}
+
+/// 鼓棒与转盘沿用原谱的物件, 拆分出的音符使用转谱的音效
+///
+/// 原谱与转谱都按开始时间排序, 只需一个游标同步前进
+fn taiko_objects(original: &[HitObject], converted: &rosu_pp::Beatmap) -> Vec<HitObject> {
+    let mut cursor = 0;
+    converted
+        .hit_objects
+        .iter()
+        .zip(converted.hit_sounds.iter())
+        .map(|(h, sound)| {
+            while original
+                .get(cursor)
+                .is_some_and(|o| o.start_time < h.start_time)
+            {
+                cursor += 1;
+            }
+            let same = original[cursor..]
+                .iter()
+                .take_while(|o| o.start_time == h.start_time)
+                .find(|o| {
+                    matches!(
+                        (&o.kind, &h.kind),
+                        (HitObjectKind::Slider(_), PpHitObjectKind::Slider(_))
+                            | (HitObjectKind::Spinner(_), PpHitObjectKind::Spinner(_))
+                            | (HitObjectKind::Circle(_), PpHitObjectKind::Circle)
+                    )
+                });
+            if let Some(same) = same {
+                return same.clone();
+            }
+
+            HitObject {
+                start_time: h.start_time,
+                kind: HitObjectKind::Circle(HitObjectCircle {
+                    pos: h.pos,
+                    new_combo: false,
+                    combo_offset: 0,
+                }),
+                samples: SampleBankInfo::default()
+                    .convert_sound_type(HitSoundType::from(u8::from(*sound))),
+            }
+        })
+        .collect()
+}
+
+/// 物件放在所在列的中间, 读取时可以得到相同的列
+///
+/// rosu-pp 转为 mania 时不保留音效, 由原谱中最近开始的物件取得, 滑条按时间取对应节点的音效
+fn mania_objects(original: &mut [HitObject], converted: &rosu_pp::Beatmap) -> Vec<HitObject> {
+    let keys = converted.cs.round().max(1.0);
+    let column_width = 512.0 / keys;
+    let mut bufs = CurveBuffers::default();
+    let end_times: Vec<f64> = original
+        .iter_mut()
+        .map(|h| h.end_time_with_bufs(&mut bufs))
+        .collect();
+    let mut cursor = 0;
+
+    converted
+        .hit_objects
+        .iter()
+        .map(|h| {
+            while original
+                .get(cursor + 1)
+                .is_some_and(|o| o.start_time <= h.start_time)
+            {
+                cursor += 1;
+            }
+            let samples = original.get(cursor).map_or_else(Vec::new, |o| {
+                source_samples(o, end_times[cursor], h.start_time)
+            });
+
+            let column = (h.pos.x / column_width).floor().min(keys - 1.0);
+            let x = ((column + 0.5) * column_width).floor();
+            let kind = match h.kind {
+                PpHitObjectKind::Hold(ref hold) => HitObjectKind::Hold(HitObjectHold {
+                    pos_x: x,
+                    duration: hold.duration,
+                }),
+                PpHitObjectKind::Spinner(ref spinner) => HitObjectKind::Spinner(HitObjectSpinner {
+                    pos: Pos::new(x, 192.0),
+                    duration: spinner.duration,
+                    new_combo: false,
+                }),
+                _ => HitObjectKind::Circle(HitObjectCircle {
+                    pos: Pos::new(x, 192.0),
+                    new_combo: false,
+                    combo_offset: 0,
+                }),
+            };
+
+            HitObject {
+                start_time: h.start_time,
+                kind,
+                samples,
+            }
+        })
+        .collect()
+}
+
+/// `time` 时原物件的音效, 滑条的头, 折返与尾各有音效
+fn source_samples(h: &HitObject, end_time: f64, time: f64) -> Vec<HitSampleInfo> {
+    let HitObjectKind::Slider(ref slider) = h.kind else {
+        return h.samples.clone();
+    };
+    let span_duration = (end_time - h.start_time) / slider.span_count() as f64;
+    if slider.node_samples.is_empty() || span_duration <= 0.0 {
+        return h.samples.clone();
+    }
+
+    let node = ((time - h.start_time) / span_duration).round().max(0.0) as usize;
+    slider.node_samples[node.min(slider.node_samples.len() - 1)].clone()
+}
+
+/// 截取 `start` 到 `end` (ms, 谱面时间) 之间的物件生成练习用的 `.osu` 文件, 结果同 [`change_rate`]
+///
+/// - 红绿线保留区间内的以及 `start - lead_in` 时生效的, 预览点设为 `start - lead_in`
+/// - 休息段与书签只保留区间内的, 关闭倒计时
+/// - 第一个物件开始新的 combo, 并调整 combo 偏移使颜色与原谱一致
+///
+/// 难度名会加上区间后缀, 音频不会被裁剪
+pub fn cut_section(
+    env: &JNIEnv,
+    local_map: &JByteArray,
+    start: f64,
+    end: f64,
+    lead_in: f64,
+) -> Result<Vec<u8>> {
+    if end <= start {
+        return Err(Error::from("invalid range"));
+    }
+
+    let mut map = get_full_map(env, local_map)?;
+    cut_map(&mut map, start, end, lead_in)?;
+    encode_map(&mut map)
+}
+
+fn cut_map(map: &mut Beatmap, start: f64, end: f64, lead_in: f64) -> Result<()> {
+    let from = (start - lead_in.max(0.0)).max(0.0);
+
+    let in_range = |h: &HitObject| h.start_time >= start && h.start_time <= end;
+    let indices: Vec<_> = combo_indices(&map.hit_objects)
+        .into_iter()
+        .zip(&map.hit_objects)
+        .filter(|(_, h)| in_range(h))
+        .map(|(index, _)| index)
+        .collect();
+    map.hit_objects.retain(in_range);
+    if map.hit_objects.is_empty() {
+        return Err(Error::from("no hit objects in range"));
+    }
+    align_combo(
+        &mut map.hit_objects,
+        &indices,
+        map.custom_combo_colors.len() as i32,
+    );
+
+    let control_points = &mut map.control_points;
+    retain_points(&mut control_points.timing_points, |p| p.time, from, end);
+    retain_points(&mut control_points.difficulty_points, |p| p.time, from, end);
+    retain_points(&mut control_points.effect_points, |p| p.time, from, end);
+    retain_points(&mut control_points.sample_points, |p| p.time, from, end);
+
+    map.breaks
+        .retain(|b| b.start_time >= start && b.end_time <= end);
+    map.bookmarks
+        .retain(|b| f64::from(*b) >= from && f64::from(*b) <= end);
+    map.preview_time = from as i32;
+    map.countdown = CountdownType::None;
+    map.version = format!(
+        "{} ({}-{})",
+        map.version,
+        format_time(start),
+        format_time(end)
+    );
+
+    Ok(())
+}
+
+/// 每个物件的 combo 序号, 规则同 [`crate::objects`]
+fn combo_indices(hit_objects: &[HitObject]) -> Vec<i32> {
+    hit_objects
+        .iter()
+        .scan(0, |index, h| {
+            if h.new_combo() {
+                *index += 1 + combo_offset(&h.kind);
+            }
+            Some(*index)
+        })
+        .collect()
+}
+
+/// 截取后第一个物件开始新的 combo, 转盘后的物件同样会开始新的 combo (解析时已标记),
+/// 转盘没有偏移, 因此由第一个圆圈或滑条的偏移使其序号与原谱的序号 `indices` 模颜色数相同;
+/// 之后的物件与原谱的 combo 标记相同, 颜色随之一致
+fn align_combo(hit_objects: &mut [HitObject], indices: &[i32], colors: i32) {
+    let mut index = 0;
+    for (i, h) in hit_objects.iter_mut().enumerate() {
+        let offset = if colors > 0 {
+            (indices[i] - index - 1).rem_euclid(colors)
+        } else {
+            0
+        };
+        match h.kind {
+            HitObjectKind::Circle(ref mut h) => {
+                h.new_combo = true;
+                h.combo_offset = offset;
+                return;
+            }
+            HitObjectKind::Slider(ref mut h) => {
+                h.new_combo = true;
+                h.combo_offset = offset;
+                return;
+            }
+            HitObjectKind::Spinner(ref mut h) => {
+                h.new_combo |= i == 0;
+                if h.new_combo {
+                    index += 1;
+                }
+            }
+            HitObjectKind::Hold(_) => return,
+        }
+    }
+}
+
+/// 保留 `(from, to]` 内的点以及 `from` 时生效的点
+fn retain_points<T>(points: &mut Vec<T>, time: impl Fn(&T) -> f64, from: f64, to: f64) {
+    let active = points.iter().rposition(|p| time(p) <= from);
+    let mut i = 0;
+    points.retain(|p| {
+        let keep = Some(i) == active || (time(p) > from && time(p) <= to);
+        i += 1;
+        keep
+    });
+}
+
+/// `mm:ss`
+fn format_time(time: f64) -> String {
+    let seconds = (time / 1000.0).max(0.0) as u32;
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// 写出 `.osu` 文件, 结果的第一个字节为 [`StatusFlag::None`]
+pub(crate) fn encode_map(map: &mut Beatmap) -> Result<Vec<u8>> {
+    let mut result = Vec::<u8>::with_capacity(4096);
+    result.put_u8(StatusFlag::None.bits());
+    map.encode(&mut result)?;
+    Ok(result)
+}
+
+/// 物件时间取整, 与 osu! 读取的整数毫秒一致
+fn scale_map(map: &mut Beatmap, rate: f64) {
+    let scale = |time: f64| time / rate;
+    let scale_int = |time: f64| (time / rate).round();
+
+    map.audio_lead_in = scale(map.audio_lead_in);
+    map.preview_time = if map.preview_time >= 0 {
+        scale_int(f64::from(map.preview_time)) as i32
+    } else {
+        map.preview_time
+    };
+    map.bookmarks
+        .iter_mut()
+        .for_each(|b| *b = scale_int(f64::from(*b)) as i32);
+
+    for b in map.breaks.iter_mut() {
+        b.start_time = scale_int(b.start_time);
+        b.end_time = scale_int(b.end_time);
+    }
+
+    let control_points = &mut map.control_points;
+    for t in control_points.timing_points.iter_mut() {
+        t.time = scale(t.time);
+        t.beat_len = scale(t.beat_len);
+    }
+    for d in control_points.difficulty_points.iter_mut() {
+        d.time = scale(d.time);
+    }
+    for e in control_points.effect_points.iter_mut() {
+        e.time = scale(e.time);
+    }
+    for s in control_points.sample_points.iter_mut() {
+        s.time = scale(s.time);
+    }
+
+    for h in map.hit_objects.iter_mut() {
+        let start_time = scale_int(h.start_time);
+        match h.kind {
+            HitObjectKind::Circle(_) => {}
+            // 滑条时长由红线与 sv 决定, 速度 (osu! 像素 / ms) 随之变快
+            HitObjectKind::Slider(ref mut s) => s.velocity *= rate,
+            HitObjectKind::Spinner(ref mut s) => {
+                s.duration = scale_int(h.start_time + s.duration) - start_time;
+            }
+            HitObjectKind::Hold(ref mut s) => {
+                s.duration = scale_int(h.start_time + s.duration) - start_time;
+            }
+        }
+        h.start_time = start_time;
+    }
+}
+
+/// 由原谱在 `rate` 倍速下的缩圈时间与 300 判定区间反推 AR 与 OD, 与原谱加 DT/HT 时相同
+fn compensate_difficulty(map: &mut Beatmap, rate: f64) {
+    let preempt = difficulty_range(f64::from(map.approach_rate), 1800.0, 1200.0, 450.0) / rate;
+    map.approach_rate = inverse_difficulty_range(preempt, 1800.0, 1200.0, 450.0) as f32;
+
+    let od_range = match map.mode {
+        GameMode::Osu => Some((80.0, 50.0, 20.0)),
+        GameMode::Taiko => Some((50.0, 35.0, 20.0)),
+        GameMode::Mania => Some((64.0, 49.0, 34.0)),
+        // catch 不使用 OD 计算判定
+        GameMode::Catch => None,
+    };
+    if let Some((min, mid, max)) = od_range {
+        let window = difficulty_range(f64::from(map.overall_difficulty), min, mid, max) / rate;
+        map.overall_difficulty = inverse_difficulty_range(window, min, mid, max) as f32;
+    }
+}
+
+fn inverse_difficulty_range(value: f64, min: f64, mid: f64, max: f64) -> f64 {
+    let difficulty = if (value - mid) * (min - mid) > 0.0 {
+        5.0 - 5.0 * (value - mid) / (min - mid)
+    } else {
+        5.0 + 5.0 * (value - mid) / (max - mid)
+    };
+
+    // 保留一位小数, 与编辑器一致
+    (difficulty.clamp(0.0, 10.0) * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 带 whistle 的圆圈, 会被拆分的短滑条 (头 clap, 尾 finish), 鼓棒, 转盘
+    const CONVERT_MAP: &str = "osu file format v14
+
+[General]
+Mode: 0
+
+[Difficulty]
+CircleSize:4
+OverallDifficulty:5
+SliderMultiplier:1.4
+SliderTickRate:1
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+100,100,0,1,2,0:0:0:0:
+100,100,500,2,0,L|200:100,1,100,8|4,0:0|0:0,0:0:0:0:
+100,100,2000,2,0,L|500:100,1,400
+256,192,4000,12,0,5000,0:0:0:0:
+";
+
+    fn sound(h: &HitObject) -> u8 {
+        u8::from(HitSoundType::from(h.samples.as_slice()))
+    }
+
+    #[test]
+    fn export_taiko() {
+        let map = convert_map(CONVERT_MAP.as_bytes(), GameMode::Taiko).unwrap();
+        assert_eq!(map.mode, GameMode::Taiko);
+        assert!(map.version.ends_with("(Taiko)"));
+
+        // 短滑条拆为两个音符, 音效取自滑条的头和尾
+        let objects: Vec<_> = map
+            .hit_objects
+            .iter()
+            .map(|h| (h.start_time, sound(h)))
+            .collect();
+        assert_eq!(
+            objects,
+            vec![(0.0, 2), (500.0, 8), (857.0, 4), (2000.0, 0), (4000.0, 0)]
+        );
+        assert!(matches!(map.hit_objects[3].kind, HitObjectKind::Slider(_)));
+        assert!(matches!(map.hit_objects[4].kind, HitObjectKind::Spinner(_)));
+    }
+
+    #[test]
+    fn export_mania() {
+        let mut map = convert_map(CONVERT_MAP.as_bytes(), GameMode::Mania).unwrap();
+        assert_eq!(map.circle_size, 5.0);
+        assert!(map.version.ends_with("(5K)"));
+        let objects: Vec<_> = map
+            .hit_objects
+            .iter()
+            .map(|h| (h.start_time, sound(h)))
+            .collect();
+        assert_eq!(
+            objects,
+            vec![(0.0, 2), (500.0, 8), (2000.0, 0), (4000.0, 0)]
+        );
+
+        // 写出后再读取得到与转谱相同的列
+        let bytes = encode_map(&mut map).unwrap();
+        let exported = rosu_pp::Beatmap::from_bytes(&bytes[1..]).unwrap();
+        let mut converted = rosu_pp::Beatmap::from_bytes(CONVERT_MAP.as_bytes()).unwrap();
+        assert!(converted.convert_in_place(GameMode::Mania).success());
+        let columns = |map: &rosu_pp::Beatmap| -> Vec<i32> {
+            map.hit_objects
+                .iter()
+                .map(|h| (h.pos.x / (512.0 / map.cs)) as i32)
+                .collect()
+        };
+        assert_eq!(exported.mode, GameMode::Mania);
+        assert_eq!(columns(&exported), columns(&converted));
+        assert_eq!(columns(&exported), vec![0, 1, 4, 3]);
+    }
+
+    fn map(ar: f32, od: f32) -> Beatmap {
+        Beatmap {
+            approach_rate: ar,
+            overall_difficulty: od,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn compensate_faster_rate() {
+        // 缩圈时间 600ms / 1.5 = 400ms 超过 AR10, 截断
+        let mut map = map(9.0, 8.0);
+        compensate_difficulty(&mut map, 1.5);
+        assert_eq!(map.approach_rate, 10.0);
+        // 判定区间 32ms / 1.5
+        assert_eq!(map.overall_difficulty, 9.8);
+    }
+
+    #[test]
+    fn compensate_slower_rate() {
+        let mut map = map(9.0, 8.0);
+        compensate_difficulty(&mut map, 0.75);
+        assert_eq!(map.approach_rate, 7.7);
+        assert_eq!(map.overall_difficulty, 6.2);
+    }
+
+    #[test]
+    fn inverse_of_difficulty_range() {
+        for difficulty in 0..=10 {
+            let difficulty = f64::from(difficulty);
+            let preempt = difficulty_range(difficulty, 1800.0, 1200.0, 450.0);
+            assert_eq!(
+                inverse_difficulty_range(preempt, 1800.0, 1200.0, 450.0),
+                difficulty
+            );
+        }
+        assert_eq!(inverse_difficulty_range(300.0, 1800.0, 1200.0, 450.0), 10.0);
+        assert_eq!(inverse_difficulty_range(2400.0, 1800.0, 1200.0, 450.0), 0.0);
+    }
+
+    #[test]
+    fn scale_times() {
+        let text = "osu file format v14
+
+[General]
+Mode: 0
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:
+256,192,2000,12,0,3000,0:0:0:0:
+";
+        let mut map = Beatmap::from_bytes(text.as_bytes()).unwrap();
+        scale_map(&mut map, 1.5);
+        assert_eq!(map.control_points.timing_points[0].beat_len, 500.0 / 1.5);
+        assert_eq!(map.hit_objects[0].start_time, 667.0);
+        assert_eq!(map.hit_objects[1].start_time, 1333.0);
+        let HitObjectKind::Spinner(ref spinner) = map.hit_objects[1].kind else {
+            panic!("expected spinner");
+        };
+        assert_eq!(spinner.duration, 2000.0 - 1333.0);
+    }
+
+    /// 三种颜色, 转盘后的圆圈隐式开始新 combo, 滑条跳过两种颜色
+    const CUT_MAP: &str = "osu file format v14
+
+[General]
+Mode: 0
+
+[Difficulty]
+SliderMultiplier:1.4
+
+[Events]
+2,5200,6800
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+3000,-50,4,2,0,100,0,0
+8000,-200,4,2,0,100,0,0
+
+[Colours]
+Combo1 : 255,0,0
+Combo2 : 0,255,0
+Combo3 : 0,0,255
+
+[HitObjects]
+100,100,1000,1,0,0:0:0:0:
+100,100,1500,1,0,0:0:0:0:
+100,100,2000,21,0,0:0:0:0:
+256,192,2500,8,0,3500,0:0:0:0:
+100,100,4000,1,0,0:0:0:0:
+100,100,4500,38,0,L|200:100,1,100
+100,100,5000,1,0,0:0:0:0:
+100,100,7000,1,0,0:0:0:0:
+";
+
+    /// 截取后每个物件的颜色 (combo 序号模颜色数) 与原谱相同, 转盘没有颜色
+    fn colors(map: &Beatmap) -> Vec<(f64, i32)> {
+        combo_indices(&map.hit_objects)
+            .into_iter()
+            .zip(&map.hit_objects)
+            .filter(|(_, h)| !matches!(h.kind, HitObjectKind::Spinner(_)))
+            .map(|(index, h)| (h.start_time, index % 3))
+            .collect()
+    }
+
+    fn assert_colors(start: f64, end: f64) {
+        let original = Beatmap::from_bytes(CUT_MAP.as_bytes()).unwrap();
+        let mut expected = colors(&original);
+        expected.retain(|(time, _)| *time >= start && *time <= end);
+
+        let mut map = Beatmap::from_bytes(CUT_MAP.as_bytes()).unwrap();
+        cut_map(&mut map, start, end, 0.0).unwrap();
+        let bytes = encode_map(&mut map).unwrap();
+        let cut = Beatmap::from_bytes(&bytes[1..]).unwrap();
+
+        assert_eq!(colors(&cut), expected);
+    }
+
+    #[test]
+    fn cut_keeps_combo_colors() {
+        // 转盘之后开始
+        assert_colors(3600.0, 8000.0);
+        // 转盘开始
+        assert_colors(2500.0, 8000.0);
+        // combo 中间开始
+        assert_colors(1500.0, 5000.0);
+    }
+
+    #[test]
+    fn cut_retains_points() {
+        let mut map = Beatmap::from_bytes(CUT_MAP.as_bytes()).unwrap();
+        cut_map(&mut map, 4000.0, 6000.0, 500.0).unwrap();
+
+        assert_eq!(map.hit_objects.len(), 3);
+        assert_eq!(map.hit_objects[0].start_time, 4000.0);
+        assert_eq!(map.control_points.timing_points.len(), 1);
+        assert_eq!(map.control_points.difficulty_points.len(), 1);
+        assert_eq!(map.control_points.difficulty_points[0].time, 3000.0);
+        assert!(map.breaks.is_empty());
+        assert_eq!(map.preview_time, 3500);
+        assert_eq!(map.version, " (00:04-00:06)");
+
+        let mut map = Beatmap::from_bytes(CUT_MAP.as_bytes()).unwrap();
+        assert!(cut_map(&mut map, 8000.0, 9000.0, 0.0).is_err());
+    }
+}
@@ -1,5 +1,29 @@
 package rosu
 
+import rosu.beatmap.BeatmapDiff
+import rosu.beatmap.BeatmapMetadata
+import rosu.beatmap.CatchStatistics
+import rosu.beatmap.DecodedBeatmap
+import rosu.beatmap.Distribution
+import rosu.beatmap.FingerprintSimilarity
+import rosu.beatmap.HardSections
+import rosu.beatmap.HitObjectInfo
+import rosu.beatmap.HitObjectList
+import rosu.beatmap.HitObjectType
+import rosu.beatmap.ManiaStatistics
+import rosu.beatmap.ModdingIssue
+import rosu.beatmap.NoteDensity
+import rosu.beatmap.OsuObjectGeometry
+import rosu.beatmap.ParseDiagnostics
+import rosu.beatmap.PatternAnalysis
+import rosu.beatmap.ReadingAnalysis
+import rosu.beatmap.ScrollSpeedAnalysis
+import rosu.beatmap.SliderGeometry
+import rosu.beatmap.SnapAnalysis
+import rosu.beatmap.SpacingStatistics
+import rosu.beatmap.TaikoStatistics
+import rosu.beatmap.TimeRange
+import rosu.beatmap.TimingAnalysis
 import rosu.osu.Mode
 import rosu.parameter.JniScoreState
 import rosu.result.*
@@ -90,11 +114,441 @@ internal object JniProcessor {
         return result
     }
 
+    @JvmStatic
+    fun bytesToBeatmapAttributes(bytes: ByteArray): JniBeatmapAttributes {
+        val buffer = ByteBuffer.wrap(bytes)
+        val head = buffer.get().toUByte()
+        val mode = when (head) {
+            Osu -> Mode.Osu
+            Taiko -> Mode.Taiko
+            Catch -> Mode.Catch
+            Mania -> Mode.Mania
+            ERROR -> {
+                throw Exception(buffer.readString())
+            }
+            else -> throw Exception("Unknown mode")
+        }
+        val result = JniBeatmapAttributes()
+        result.mode = mode
+        result.ar = buffer.double
+        result.od = buffer.double
+        result.cs = buffer.double
+        result.hp = buffer.double
+        result.clockRate = buffer.double
+        result.preempt = buffer.double
+        result.radius = buffer.double
+        result.hitWindows = List(buffer.int) { buffer.double }
+        return result
+    }
+
+    @JvmStatic
+    fun bytesToMetadata(bytes: ByteArray): BeatmapMetadata {
+        val buffer = ByteBuffer.wrap(readJniBytes(bytes))
+        return BeatmapMetadata(
+            title = buffer.readString(),
+            titleUnicode = buffer.readString(),
+            artist = buffer.readString(),
+            artistUnicode = buffer.readString(),
+            creator = buffer.readString(),
+            version = buffer.readString(),
+            source = buffer.readString(),
+            tags = buffer.readString(),
+            beatmapId = buffer.int,
+            beatmapSetId = buffer.int,
+            audioFile = buffer.readString(),
+            previewTime = buffer.int,
+            backgroundFile = buffer.readString(),
+        )
+    }
+
+    @JvmStatic
+    fun bytesToTiming(bytes: ByteArray): TimingAnalysis {
+        val buffer = ByteBuffer.wrap(readJniBytes(bytes))
+        val bpmMin = buffer.double
+        val bpmMax = buffer.double
+        val bpmMostCommon = buffer.double
+        val totalLength = buffer.double
+        val drainLength = buffer.double
+        val timingPoints = List(buffer.int) {
+            TimingAnalysis.TimingPoint(
+                time = buffer.double,
+                beatLength = buffer.double,
+                bpm = buffer.double,
+                timeSignature = buffer.int,
+                omitFirstBarLine = buffer.get() != 0.toByte(),
+            )
+        }
+        val difficultyPoints = List(buffer.int) {
+            TimingAnalysis.DifficultyPoint(
+                time = buffer.double,
+                sliderVelocity = buffer.double,
+            )
+        }
+        val breaks = buffer.readTimeRanges()
+        val kiaiSections = buffer.readTimeRanges()
+        val drainSegments = buffer.readTimeRanges()
+        return TimingAnalysis(
+            bpmMin, bpmMax, bpmMostCommon, totalLength, drainLength, timingPoints, difficultyPoints,
+            breaks, kiaiSections, drainSegments
+        )
+    }
+
+    @JvmStatic
+    fun bytesToHitObjects(bytes: ByteArray): HitObjectList {
+        val buffer = ByteBuffer.wrap(bytes)
+        val mode = buffer.readMode()
+        val objects = List(buffer.int) {
+            HitObjectInfo(
+                startTime = buffer.double,
+                endTime = buffer.double,
+                x = buffer.float,
+                y = buffer.float,
+                type = HitObjectType.getType(buffer.get().toInt()),
+                newCombo = buffer.get() != 0.toByte(),
+                comboIndex = buffer.int,
+                indexInCombo = buffer.int,
+                hitSound = buffer.get().toInt(),
+            )
+        }
+        return HitObjectList(mode, objects)
+    }
+
+    @JvmStatic
+    fun bytesToOsuGeometry(bytes: ByteArray): List<OsuObjectGeometry> {
+        val buffer = ByteBuffer.wrap(bytes)
+        buffer.readMode()
+        return List(buffer.int) {
+            val startTime = buffer.double
+            val endTime = buffer.double
+            val x = buffer.float
+            val y = buffer.float
+            val stackHeight = buffer.int
+            val type = HitObjectType.getType(buffer.get().toInt())
+            val slider = if (type == HitObjectType.Slider) {
+                val repeats = buffer.int
+                val path = List(buffer.int) {
+                    SliderGeometry.Point(x = buffer.float, y = buffer.float)
+                }
+                val ticks = List(buffer.int) {
+                    SliderGeometry.Tick(time = buffer.double, x = buffer.float, y = buffer.float)
+                }
+                SliderGeometry(repeats, path, ticks)
+            } else {
+                null
+            }
+            OsuObjectGeometry(startTime, endTime, x, y, stackHeight, type, slider)
+        }
+    }
+
+    @JvmStatic
+    fun bytesToDensity(bytes: ByteArray): NoteDensity {
+        val buffer = ByteBuffer.wrap(bytes)
+        return NoteDensity(
+            mode = buffer.readMode(),
+            window = buffer.double,
+            peakNps = buffer.double,
+            averageNps = buffer.double,
+            startTime = buffer.double,
+            nps = List(buffer.int) { buffer.double },
+        )
+    }
+
+    @JvmStatic
+    fun bytesToPatterns(bytes: ByteArray): PatternAnalysis {
+        val buffer = ByteBuffer.wrap(bytes)
+        buffer.readMode()
+        return PatternAnalysis(
+            stream = buffer.double,
+            burst = buffer.double,
+            jump = buffer.double,
+            alt = buffer.double,
+            slider = buffer.double,
+            other = buffer.double,
+            longestStream = buffer.int,
+            streamCount = buffer.int,
+            burstCount = buffer.int,
+            aim = buffer.double,
+            speed = buffer.double,
+            aimRatio = buffer.double,
+        )
+    }
+
+    @JvmStatic
+    fun bytesToSpacing(bytes: ByteArray): SpacingStatistics {
+        val buffer = ByteBuffer.wrap(bytes)
+        buffer.readMode()
+        return SpacingStatistics(
+            radius = buffer.float,
+            distance = buffer.readDistribution(),
+            distanceInRadii = buffer.readDistribution(),
+            angle = buffer.readDistribution(),
+            velocity = buffer.readDistribution(),
+        )
+    }
+
+    @JvmStatic
+    fun bytesToSnap(bytes: ByteArray): SnapAnalysis {
+        val buffer = ByteBuffer.wrap(readJniBytes(bytes))
+        val total = buffer.int
+        val divisors = LinkedHashMap<Int, Int>()
+        repeat(buffer.int) {
+            divisors[buffer.int] = buffer.int
+        }
+        val unsnapped = List(buffer.int) {
+            SnapAnalysis.UnsnappedObject(
+                index = buffer.int,
+                time = buffer.double,
+                offset = buffer.double,
+            )
+        }
+        return SnapAnalysis(total, divisors, unsnapped)
+    }
+
+    @JvmStatic
+    fun bytesToScrollSpeed(bytes: ByteArray): ScrollSpeedAnalysis {
+        val buffer = ByteBuffer.wrap(readJniBytes(bytes))
+        return ScrollSpeedAnalysis(
+            baseVelocity = buffer.double,
+            min = buffer.double,
+            max = buffer.double,
+            average = buffer.double,
+            changes = buffer.int,
+            timeline = List(buffer.int) {
+                ScrollSpeedAnalysis.ScrollPoint(
+                    time = buffer.double,
+                    bpm = buffer.double,
+                    sv = buffer.double,
+                    speed = buffer.double,
+                )
+            },
+        )
+    }
+
+    @JvmStatic
+    fun bytesToHardSections(bytes: ByteArray): HardSections {
+        val buffer = ByteBuffer.wrap(bytes)
+        val mode = buffer.readMode()
+        val sectionLength = buffer.double
+        val averageStrain = buffer.double
+        val skills = List(buffer.int) { buffer.readString() }
+        val readSections = {
+            List(buffer.int) {
+                HardSections.StrainSection(
+                    start = buffer.double,
+                    end = buffer.double,
+                    strain = buffer.double,
+                    contribution = List(skills.size) { buffer.double },
+                )
+            }
+        }
+        return HardSections(
+            mode = mode,
+            sectionLength = sectionLength,
+            averageStrain = averageStrain,
+            skills = skills,
+            hardest = readSections(),
+            spikes = readSections(),
+        )
+    }
+
+    @JvmStatic
+    fun bytesToReading(bytes: ByteArray): ReadingAnalysis {
+        val buffer = ByteBuffer.wrap(bytes)
+        buffer.readMode()
+        return ReadingAnalysis(
+            preempt = buffer.double,
+            fadeIn = buffer.double,
+            hidden = buffer.get() != 0.toByte(),
+            peak = buffer.int,
+            peakTime = buffer.double,
+            average = buffer.double,
+            timeline = List(buffer.int) {
+                ReadingAnalysis.VisiblePoint(time = buffer.double, visible = buffer.int)
+            },
+        )
+    }
+
+    @JvmStatic
+    fun bytesToMania(bytes: ByteArray): ManiaStatistics {
+        val buffer = ByteBuffer.wrap(bytes)
+        buffer.readMode()
+        val keys = buffer.int
+        return ManiaStatistics(
+            keys = keys,
+            columns = List(keys) { buffer.int },
+            notes = buffer.int,
+            longNotes = buffer.int,
+            longNoteRatio = buffer.double,
+            averageLongNoteLength = buffer.double,
+            rows = buffer.int,
+            chords = buffer.int,
+            jacks = buffer.int,
+            chordRatio = buffer.double,
+            averageRowSize = buffer.double,
+            jackRatio = buffer.double,
+            chordsPerSecond = buffer.double,
+            jacksPerSecond = buffer.double,
+            left = buffer.int,
+            right = buffer.int,
+            middle = buffer.int,
+        )
+    }
+
+    @JvmStatic
+    fun bytesToTaiko(bytes: ByteArray): TaikoStatistics {
+        val buffer = ByteBuffer.wrap(bytes)
+        buffer.readMode()
+        return TaikoStatistics(
+            don = buffer.int,
+            kat = buffer.int,
+            finisher = buffer.int,
+            donRatio = buffer.double,
+            longestMono = buffer.int,
+            rolls = buffer.int,
+            rollDuration = buffer.double,
+            swells = buffer.int,
+            swellDuration = buffer.double,
+            colourChanges = buffer.int,
+            colourChangeRatio = buffer.double,
+            colourChangesPerSecond = buffer.double,
+        )
+    }
+
+    @JvmStatic
+    fun bytesToCatch(bytes: ByteArray): CatchStatistics {
+        val buffer = ByteBuffer.wrap(bytes)
+        buffer.readMode()
+        return CatchStatistics(
+            fruits = buffer.int,
+            droplets = buffer.int,
+            tinyDroplets = buffer.int,
+            bananas = buffer.int,
+            catcherWidth = buffer.float,
+            hyperDashes = List(buffer.int) {
+                CatchStatistics.HyperDash(
+                    time = buffer.double,
+                    x = buffer.float,
+                    targetTime = buffer.double,
+                    targetX = buffer.float,
+                )
+            },
+            totalMovement = buffer.double,
+            movementSection = buffer.double,
+            movement = List(buffer.int) { buffer.double },
+        )
+    }
+
+    @JvmStatic
+    fun bytesToDiff(bytes: ByteArray): BeatmapDiff {
+        val buffer = ByteBuffer.wrap(readJniBytes(bytes))
+        return BeatmapDiff(
+            fields = List(buffer.int) {
+                BeatmapDiff.FieldChange(
+                    name = buffer.readString(),
+                    old = buffer.readString(),
+                    new = buffer.readString(),
+                )
+            },
+            timing = List(buffer.int) {
+                BeatmapDiff.TimingChange(
+                    change = BeatmapDiff.ChangeType.getType(buffer.get().toInt()),
+                    redLine = buffer.get() != 0.toByte(),
+                    time = buffer.double,
+                    old = buffer.double,
+                    new = buffer.double,
+                )
+            },
+            objects = List(buffer.int) {
+                BeatmapDiff.ObjectChange(
+                    change = BeatmapDiff.ChangeType.getType(buffer.get().toInt()),
+                    type = HitObjectType.getType(buffer.get().toInt()),
+                    time = buffer.double,
+                    oldX = buffer.float,
+                    oldY = buffer.float,
+                    newX = buffer.float,
+                    newY = buffer.float,
+                )
+            },
+            unchangedObjects = buffer.int,
+            oldStars = buffer.double,
+            newStars = buffer.double,
+            gameplayChanged = buffer.get() != 0.toByte(),
+        )
+    }
+
+    @JvmStatic
+    fun bytesToSimilarity(bytes: ByteArray): FingerprintSimilarity {
+        val buffer = ByteBuffer.wrap(readJniBytes(bytes))
+        return FingerprintSimilarity(
+            rhythm = buffer.double,
+            pattern = buffer.double,
+            overall = buffer.double,
+        )
+    }
+
+    @JvmStatic
+    fun bytesToIssues(bytes: ByteArray): List<ModdingIssue> {
+        val buffer = ByteBuffer.wrap(readJniBytes(bytes))
+        return List(buffer.int) {
+            ModdingIssue(
+                severity = ModdingIssue.Severity.getType(buffer.get().toInt()),
+                check = ModdingIssue.Check.getType(buffer.get().toInt()),
+                mapIndex = buffer.int,
+                time = buffer.double.takeUnless { it.isNaN() },
+                message = buffer.readString(),
+            )
+        }
+    }
+
+    @JvmStatic
+    fun bytesToDiagnostics(bytes: ByteArray): ParseDiagnostics {
+        val buffer = ByteBuffer.wrap(readJniBytes(bytes))
+        val formatVersion = buffer.int.takeUnless { it == 0 }
+        val warnings = List(buffer.int) {
+            ParseDiagnostics.ParseWarning(
+                kind = ParseDiagnostics.Kind.getType(buffer.get().toInt()),
+                line = buffer.int.takeUnless { it == 0 },
+                message = buffer.readString(),
+            )
+        }
+        return ParseDiagnostics(formatVersion, warnings)
+    }
+
+    @JvmStatic
+    fun bytesToDecodedMap(bytes: ByteArray): DecodedBeatmap {
+        val buffer = ByteBuffer.wrap(readJniBytes(bytes))
+        val encoding = buffer.readString()
+        val map = ByteArray(buffer.remaining())
+        buffer.get(map)
+        return DecodedBeatmap(encoding, map)
+    }
+
+    private fun ByteBuffer.readDistribution() = Distribution(
+        average = double,
+        max = double,
+        percentiles = List(Distribution.PERCENTILES.size) { double },
+        binWidth = double,
+        histogram = List(int) { int },
+    )
+
+    private fun ByteBuffer.readMode(): Mode = when (get().toUByte()) {
+        Osu -> Mode.Osu
+        Taiko -> Mode.Taiko
+        Catch -> Mode.Catch
+        Mania -> Mode.Mania
+        ERROR -> throw Exception(readString())
+        else -> throw Exception("Unknown mode")
+    }
+
+    private fun ByteBuffer.readTimeRanges(): List<TimeRange> = List(int) {
+        TimeRange(start = double, end = double)
+    }
+
     private fun ByteBuffer.readString(): String {
         val length = int
         val bytes = ByteArray(length)
         get(bytes)
-        return String(bytes)
+        return String(bytes, Charsets.UTF_8)
     }
 
     fun readJniBytes(bytes: ByteArray): ByteArray = bytes.apply {
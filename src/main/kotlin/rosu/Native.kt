@@ -57,6 +57,92 @@ internal class Native private constructor() {
     @JvmName("releaseCalculate")
     external fun releaseCalculate(ptr: Long): ByteArray
 
+    @JvmName("getBeatmapAttributes")
+    external fun getBeatmapAttributes(localMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    @JvmName("getBeatmapAttributesByValue")
+    external fun getBeatmapAttributesByValue(value: ByteArray): ByteArray
+
+    /**********************************************************************************************/
+    @JvmName("parseMetadata")
+    external fun parseMetadata(localMap: ByteArray): ByteArray
+
+    @JvmName("analyzeTiming")
+    external fun analyzeTiming(localMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    @JvmName("listHitObjects")
+    external fun listHitObjects(localMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    @JvmName("getSliderGeometry")
+    external fun getSliderGeometry(localMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    @JvmName("analyzeDensity")
+    external fun analyzeDensity(localMap: ByteArray, mapAttr: ByteArray, window: Double): ByteArray
+
+    @JvmName("classifyPatterns")
+    external fun classifyPatterns(localMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    @JvmName("getSpacingStatistics")
+    external fun getSpacingStatistics(localMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    @JvmName("analyzeSnap")
+    external fun analyzeSnap(localMap: ByteArray): ByteArray
+
+    @JvmName("analyzeScrollSpeed")
+    external fun analyzeScrollSpeed(localMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    @JvmName("findHardSections")
+    external fun findHardSections(localMap: ByteArray, mapAttr: ByteArray, window: Double, top: Int, spikeFactor: Double): ByteArray
+
+    @JvmName("analyzeReading")
+    external fun analyzeReading(localMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    @JvmName("analyzeMania")
+    external fun analyzeMania(localMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    @JvmName("analyzeTaiko")
+    external fun analyzeTaiko(localMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    @JvmName("analyzeCatch")
+    external fun analyzeCatch(localMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    /**********************************************************************************************/
+    @JvmName("changeRate")
+    external fun changeRate(localMap: ByteArray, rate: Double, compensate: Boolean): ByteArray
+
+    @JvmName("exportConverted")
+    external fun exportConverted(localMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    @JvmName("cutSection")
+    external fun cutSection(localMap: ByteArray, start: Double, end: Double, leadIn: Double): ByteArray
+
+    @JvmName("mapToJson")
+    external fun mapToJson(localMap: ByteArray): ByteArray
+
+    @JvmName("jsonToMap")
+    external fun jsonToMap(json: ByteArray): ByteArray
+
+    @JvmName("diffMaps")
+    external fun diffMaps(oldMap: ByteArray, newMap: ByteArray, mapAttr: ByteArray): ByteArray
+
+    @JvmName("getFingerprint")
+    external fun getFingerprint(localMap: ByteArray): ByteArray
+
+    @JvmName("compareFingerprints")
+    external fun compareFingerprints(a: ByteArray, b: ByteArray): ByteArray
+
+    @JvmName("checkBeatmap")
+    external fun checkBeatmap(localMap: ByteArray): ByteArray
+
+    @JvmName("checkMapset")
+    external fun checkMapset(localMaps: Array<ByteArray>): ByteArray
+
+    @JvmName("decodeMap")
+    external fun decodeMap(localMap: ByteArray, encoding: String): ByteArray
+
+    @JvmName("parseDiagnostics")
+    external fun parseDiagnostics(localMap: ByteArray, strict: Boolean): ByteArray
+
     /**********************************************************************************************/
     @JvmName("createCollection")
     external fun createCollection(collection: OsuCollection): ByteArray
@@ -0,0 +1,310 @@
+package rosu
+
+import rosu.beatmap.BeatmapDiff
+import rosu.beatmap.BeatmapMetadata
+import rosu.beatmap.CatchStatistics
+import rosu.beatmap.DecodedBeatmap
+import rosu.beatmap.FingerprintSimilarity
+import rosu.beatmap.HardSections
+import rosu.beatmap.HitObjectList
+import rosu.beatmap.ManiaStatistics
+import rosu.beatmap.ModdingIssue
+import rosu.beatmap.NoteDensity
+import rosu.beatmap.OsuObjectGeometry
+import rosu.beatmap.ParseDiagnostics
+import rosu.beatmap.PatternAnalysis
+import rosu.beatmap.ReadingAnalysis
+import rosu.beatmap.ScrollSpeedAnalysis
+import rosu.beatmap.SnapAnalysis
+import rosu.beatmap.SpacingStatistics
+import rosu.beatmap.TaikoStatistics
+import rosu.beatmap.TimingAnalysis
+import rosu.parameter.JniMapAttr
+
+object OsuBeatmap {
+    private val native = Native.instance
+
+    @JvmStatic
+    @Suppress("unused")
+    fun parseMetadata(map: ByteArray): BeatmapMetadata {
+        val bytes = native.parseMetadata(map)
+        return JniProcessor.bytesToMetadata(bytes)
+    }
+
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun analyzeTiming(map: ByteArray, attr: JniMapAttr = JniMapAttr()): TimingAnalysis {
+        val bytes = native.analyzeTiming(map, attr.toBytes())
+        return JniProcessor.bytesToTiming(bytes)
+    }
+
+    /**
+     * 列出所有物件, [attr] 指定模式时会转谱, 带有 HR 时 osu! 模式的物件会上下翻转
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun listHitObjects(map: ByteArray, attr: JniMapAttr = JniMapAttr()): HitObjectList {
+        val bytes = native.listHitObjects(map, attr.toBytes())
+        return JniProcessor.bytesToHitObjects(bytes)
+    }
+
+    /**
+     * osu! 谱面的滑条曲线, tick 以及堆叠结果, [attr] 的 mods 会影响 HR 翻转与堆叠
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun getSliderGeometry(map: ByteArray, attr: JniMapAttr = JniMapAttr()): List<OsuObjectGeometry> {
+        val bytes = native.getSliderGeometry(map, attr.toBytes())
+        return JniProcessor.bytesToOsuGeometry(bytes)
+    }
+
+    /**
+     * 每秒物件数, [window] 为统计区间的长度 (ms)
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun analyzeDensity(map: ByteArray, attr: JniMapAttr = JniMapAttr(), window: Double = 1000.0): NoteDensity {
+        val bytes = native.analyzeDensity(map, attr.toBytes(), window)
+        return JniProcessor.bytesToDensity(bytes)
+    }
+
+    /**
+     * osu! 谱面的 stream / burst / jump / alt 等 pattern 占比, mods 与倍速处理同 [Rosu.calculate]
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun classifyPatterns(map: ByteArray, attr: JniMapAttr = JniMapAttr()): PatternAnalysis {
+        val bytes = native.classifyPatterns(map, attr.toBytes())
+        return JniProcessor.bytesToPatterns(bytes)
+    }
+
+    /**
+     * osu! 谱面的间距, 角度与速度分布, [attr] 的 HR / EZ 会影响翻转与圆圈大小
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun getSpacingStatistics(map: ByteArray, attr: JniMapAttr = JniMapAttr()): SpacingStatistics {
+        val bytes = native.getSpacingStatistics(map, attr.toBytes())
+        return JniProcessor.bytesToSpacing(bytes)
+    }
+
+    /**
+     * 物件在 1/1, 1/2, 1/4, 1/8, 1/3, 1/6 上的数量, 以及未对齐的物件
+     */
+    @JvmStatic
+    @Suppress("unused")
+    fun analyzeSnap(map: ByteArray): SnapAnalysis {
+        val bytes = native.analyzeSnap(map)
+        return JniProcessor.bytesToSnap(bytes)
+    }
+
+    /**
+     * 由红线 bpm, 绿线 sv 与 slider multiplier 得到的滚动速度变化, 主要用于 taiko 与 mania
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun analyzeScrollSpeed(map: ByteArray, attr: JniMapAttr = JniMapAttr()): ScrollSpeedAnalysis {
+        val bytes = native.analyzeScrollSpeed(map, attr.toBytes())
+        return JniProcessor.bytesToScrollSpeed(bytes)
+    }
+
+    /**
+     * 按 strain 找出长度为 [window] (ms) 的最难 [top] 段,
+     * 以及强度超过平均值 [spikeFactor] 倍的区间, mods 与倍速处理同 [Rosu.calculate]
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun findHardSections(
+        map: ByteArray,
+        attr: JniMapAttr = JniMapAttr(),
+        window: Double = 5000.0,
+        top: Int = 5,
+        spikeFactor: Double = 1.5,
+    ): HardSections {
+        val bytes = native.findHardSections(map, attr.toBytes(), window, top, spikeFactor)
+        return JniProcessor.bytesToHardSections(bytes)
+    }
+
+    /**
+     * osu! 谱面每个时刻同时可见的物件数, 由 AR, mods, 倍速以及 HD 的淡出决定
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun analyzeReading(map: ByteArray, attr: JniMapAttr = JniMapAttr()): ReadingAnalysis {
+        val bytes = native.analyzeReading(map, attr.toBytes())
+        return JniProcessor.bytesToReading(bytes)
+    }
+
+    /**
+     * mania 谱面的列, 长条, 叠键与左右手统计, [attr] 指定 mania 时也可用于转谱
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun analyzeMania(map: ByteArray, attr: JniMapAttr = JniMapAttr()): ManiaStatistics {
+        val bytes = native.analyzeMania(map, attr.toBytes())
+        return JniProcessor.bytesToMania(bytes)
+    }
+
+    /**
+     * taiko 谱面的 don / kat, 大音符, 鼓棒与转盘统计, [attr] 指定 taiko 时也可用于转谱
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun analyzeTaiko(map: ByteArray, attr: JniMapAttr = JniMapAttr()): TaikoStatistics {
+        val bytes = native.analyzeTaiko(map, attr.toBytes())
+        return JniProcessor.bytesToTaiko(bytes)
+    }
+
+    /**
+     * catch 谱面的水果, droplet, 香蕉与 hyperdash 统计, [attr] 指定 catch 时也可用于转谱
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun analyzeCatch(map: ByteArray, attr: JniMapAttr = JniMapAttr()): CatchStatistics {
+        val bytes = native.analyzeCatch(map, attr.toBytes())
+        return JniProcessor.bytesToCatch(bytes)
+    }
+
+    /**
+     * 生成变速后的 .osu 文件, [compensate] 为 true 时调整 AR 与 OD, 与原谱开启同样倍速 (如 DT) 时一致, 音频需要另行处理
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun changeRate(map: ByteArray, rate: Double, compensate: Boolean = false): ByteArray {
+        val bytes = native.changeRate(map, rate, compensate)
+        return JniProcessor.readJniBytes(bytes)
+    }
+
+    /**
+     * 把转谱结果导出为 .osu 文件, [attr] 需要指定目标模式, mania 会按转谱的键数与列写出
+     */
+    @JvmStatic
+    @Suppress("unused")
+    fun exportConverted(map: ByteArray, attr: JniMapAttr): ByteArray {
+        val bytes = native.exportConverted(map, attr.toBytes())
+        return JniProcessor.readJniBytes(bytes)
+    }
+
+    /**
+     * 截取 [start] 到 [end] (ms) 之间的物件生成练习用的 .osu 文件, 保留 [leadIn] 的准备时间, 难度名会加上区间后缀
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun cutSection(map: ByteArray, start: Double, end: Double, leadIn: Double = 2000.0): ByteArray {
+        val bytes = native.cutSection(map, start, end, leadIn)
+        return JniProcessor.readJniBytes(bytes)
+    }
+
+    /**
+     * 把 .osu 文件转为包含全部分区的 json, 可以用 [fromJson] 写回
+     *
+     * 解析器不读取的事件 (故事板, 视频等) 与分区按原文逐行保存在 `events.raw` 与 `extraSections` 中, 写回时原样输出
+     */
+    @JvmStatic
+    @Suppress("unused")
+    fun toJson(map: ByteArray): String {
+        val bytes = native.mapToJson(map)
+        return String(JniProcessor.readJniBytes(bytes), Charsets.UTF_8)
+    }
+
+    /**
+     * 把 [toJson] 格式的 json 写回 .osu 文件
+     */
+    @JvmStatic
+    @Suppress("unused")
+    fun fromJson(json: String): ByteArray {
+        val bytes = native.jsonToMap(json.toByteArray(Charsets.UTF_8))
+        return JniProcessor.readJniBytes(bytes)
+    }
+
+    /**
+     * 比较同一谱面的两个版本, 星级按 [attr] 的 mods 计算
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun diff(oldMap: ByteArray, newMap: ByteArray, attr: JniMapAttr = JniMapAttr()): BeatmapDiff {
+        val bytes = native.diffMaps(oldMap, newMap, attr.toBytes())
+        return JniProcessor.bytesToDiff(bytes)
+    }
+
+    /**
+     * 由物件的节奏与位置计算谱面指纹, 与 offset 和元数据无关, 结果可以直接保存并用 [compareFingerprints] 比较
+     */
+    @JvmStatic
+    @Suppress("unused")
+    fun getFingerprint(map: ByteArray): ByteArray {
+        val bytes = native.getFingerprint(map)
+        return JniProcessor.readJniBytes(bytes)
+    }
+
+    /**
+     * 比较两个 [getFingerprint] 的结果
+     */
+    @JvmStatic
+    @Suppress("unused")
+    fun compareFingerprints(a: ByteArray, b: ByteArray): FingerprintSimilarity {
+        val bytes = native.compareFingerprints(a, b)
+        return JniProcessor.bytesToSimilarity(bytes)
+    }
+
+    /**
+     * 类似 AiMod 的谱面检查, 包括未对齐的物件 (与最近的分音线相差超过 1ms), 休息段内的物件, 重叠物件, drain time, 预览点, slider velocity 与转盘长度
+     */
+    @JvmStatic
+    @Suppress("unused")
+    fun check(map: ByteArray): List<ModdingIssue> {
+        val bytes = native.checkBeatmap(map)
+        return JniProcessor.bytesToIssues(bytes)
+    }
+
+    /**
+     * 检查同一图组的多个难度, 额外比较难度间的元数据, [ModdingIssue.mapIndex] 为难度在 [maps] 中的序号
+     */
+    @JvmStatic
+    @Suppress("unused")
+    fun checkMapset(maps: List<ByteArray>): List<ModdingIssue> {
+        val bytes = native.checkMapset(maps.toTypedArray())
+        return JniProcessor.bytesToIssues(bytes)
+    }
+
+    /**
+     * 检查谱面文件, 报告未知的分区, 无法解析的行, 缺少红线或物件与不支持的格式版本
+     *
+     * 默认会跳过无法解析的行, [strict] 为 true 时有任何问题都会抛出异常
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun validate(map: ByteArray, strict: Boolean = false): ParseDiagnostics {
+        val bytes = native.parseDiagnostics(map, strict)
+        return JniProcessor.bytesToDiagnostics(bytes)
+    }
+
+    /**
+     * 将 Shift-JIS, GBK, UTF-16 等编码的谱面转换为 UTF-8, [encoding] 为 null 时根据 BOM 与内容自动识别
+     *
+     * 其他读取谱面的方法都会自动识别编码, 识别错误时可以用这个方法指定编码
+     */
+    @JvmStatic
+    @JvmOverloads
+    @Suppress("unused")
+    fun decode(map: ByteArray, encoding: String? = null): DecodedBeatmap {
+        val bytes = native.decodeMap(map, encoding ?: "")
+        return JniProcessor.bytesToDecodedMap(bytes)
+    }
+}
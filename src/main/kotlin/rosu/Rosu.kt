@@ -1,7 +1,9 @@
 package rosu
 
 import rosu.parameter.JniMapAttr
+import rosu.parameter.JniMapValue
 import rosu.parameter.JniScore
+import rosu.result.JniBeatmapAttributes
 import rosu.result.JniCalculate
 import rosu.result.JniResult
 
@@ -42,6 +44,18 @@ object Rosu {
         calculate.close()
     }
 
+    @JvmStatic
+    fun getBeatmapAttributes(map: ByteArray, attr: JniMapAttr) : JniBeatmapAttributes {
+        val p = native.getBeatmapAttributes(map, attr.toBytes())
+        return JniProcessor.bytesToBeatmapAttributes(p)
+    }
+
+    @JvmStatic
+    fun getBeatmapAttributes(value: JniMapValue) : JniBeatmapAttributes {
+        val p = native.getBeatmapAttributesByValue(value.toBytes())
+        return JniProcessor.bytesToBeatmapAttributes(p)
+    }
+
     internal fun releaseCalculate(ptr: Long) {
         val result = native.releaseCalculate(ptr)
         if (result.isNotEmpty()) throw Exception(String(result))
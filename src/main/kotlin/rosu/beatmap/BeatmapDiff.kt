@@ -0,0 +1,59 @@
+package rosu.beatmap
+
+/**
+ * 同一谱面两个版本的差异, 时间为谱面时间
+ *
+ * [fields] 包含元数据与难度设置, [gameplayChanged] 为 true 时物件, 红绿线或难度设置有变化, 旧成绩需要重新计算
+ */
+data class BeatmapDiff(
+    val fields: List<FieldChange>,
+    val timing: List<TimingChange>,
+    val objects: List<ObjectChange>,
+    val unchangedObjects: Int,
+    val oldStars: Double,
+    val newStars: Double,
+    val gameplayChanged: Boolean,
+) {
+    val starsDelta: Double
+        get() = newStars - oldStars
+
+    enum class ChangeType {
+        Added,
+        Removed,
+        Modified;
+
+        companion object {
+            fun getType(i: Int) = entries[i]
+        }
+    }
+
+    data class FieldChange(
+        val name: String,
+        val old: String,
+        val new: String,
+    )
+
+    /**
+     * 红线的值为 beat length, 绿线为 slider velocity, 新增与删除时缺少的一侧为 0
+     */
+    data class TimingChange(
+        val change: ChangeType,
+        val redLine: Boolean,
+        val time: Double,
+        val old: Double,
+        val new: Double,
+    )
+
+    /**
+     * 新增与删除时缺少一侧的坐标为 0
+     */
+    data class ObjectChange(
+        val change: ChangeType,
+        val type: HitObjectType,
+        val time: Double,
+        val oldX: Float,
+        val oldY: Float,
+        val newX: Float,
+        val newY: Float,
+    )
+}
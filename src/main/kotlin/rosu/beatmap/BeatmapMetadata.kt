@@ -0,0 +1,17 @@
+package rosu.beatmap
+
+data class BeatmapMetadata(
+    val title: String,
+    val titleUnicode: String,
+    val artist: String,
+    val artistUnicode: String,
+    val creator: String,
+    val version: String,
+    val source: String,
+    val tags: String,
+    val beatmapId: Int,
+    val beatmapSetId: Int,
+    val audioFile: String,
+    val previewTime: Int,
+    val backgroundFile: String,
+)
@@ -0,0 +1,28 @@
+package rosu.beatmap
+
+/**
+ * catch 谱面的物件与 hyperdash 统计, 时间 (ms) 已按倍速换算
+ *
+ * [movement] 为从第一个物件开始, 每 [movementSection] 内接盘的横向移动距离
+ */
+data class CatchStatistics(
+    val fruits: Int,
+    val droplets: Int,
+    val tinyDroplets: Int,
+    val bananas: Int,
+    val catcherWidth: Float,
+    val hyperDashes: List<HyperDash>,
+    val totalMovement: Double,
+    val movementSection: Double,
+    val movement: List<Double>,
+) {
+    /**
+     * 从 ([time], [x]) 冲向 ([targetTime], [targetX]) 的 hyperdash
+     */
+    data class HyperDash(
+        val time: Double,
+        val x: Float,
+        val targetTime: Double,
+        val targetX: Float,
+    )
+}
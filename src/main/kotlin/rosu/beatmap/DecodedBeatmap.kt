@@ -0,0 +1,19 @@
+package rosu.beatmap
+
+/**
+ * 转换为 UTF-8 的谱面
+ *
+ * [encoding] 为实际使用的编码名称, 如 `Shift_JIS`, `GBK`, `UTF-16LE`, [map] 可以直接传给其他方法
+ */
+data class DecodedBeatmap(
+    val encoding: String,
+    val map: ByteArray,
+) {
+    override fun equals(other: Any?): Boolean {
+        if (this === other) return true
+        if (other !is DecodedBeatmap) return false
+        return encoding == other.encoding && map.contentEquals(other.map)
+    }
+
+    override fun hashCode(): Int = 31 * encoding.hashCode() + map.contentHashCode()
+}
@@ -0,0 +1,24 @@
+package rosu.beatmap
+
+/**
+ * 数值分布, [percentiles] 依次为 [PERCENTILES] 对应的百分位
+ *
+ * [histogram] 第 i 项为 `[i * binWidth, (i + 1) * binWidth)` 内的数量, 区间数有上限, 最后一项包含更大的值
+ */
+data class Distribution(
+    val average: Double,
+    val max: Double,
+    val percentiles: List<Double>,
+    val binWidth: Double,
+    val histogram: List<Int>,
+) {
+    /**
+     * 只提供 [PERCENTILES] 中的百分位, 其他值返回 null
+     */
+    fun percentile(p: Int): Double? = percentiles.getOrNull(PERCENTILES.indexOf(p))
+
+    companion object {
+        @JvmField
+        val PERCENTILES = listOf(25, 50, 75, 90, 95, 99)
+    }
+}
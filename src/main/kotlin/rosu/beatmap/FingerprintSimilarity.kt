@@ -0,0 +1,12 @@
+package rosu.beatmap
+
+/**
+ * 两个谱面指纹的相似度, 均为 0 ~ 1
+ *
+ * [rhythm] 与 [pattern] 分别为节奏与位置片段的相似度, [overall] 为两者平均后乘以物件数量之比
+ */
+data class FingerprintSimilarity(
+    val rhythm: Double,
+    val pattern: Double,
+    val overall: Double,
+)
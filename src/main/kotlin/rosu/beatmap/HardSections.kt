@@ -0,0 +1,27 @@
+package rosu.beatmap
+
+import rosu.osu.Mode
+
+/**
+ * 最难的若干段与难度突增区间, 时间 (ms) 已按倍速换算
+ *
+ * [skills] 为各项 skill 的名称, 与 [StrainSection.contribution] 一一对应
+ */
+data class HardSections(
+    val mode: Mode,
+    val sectionLength: Double,
+    val averageStrain: Double,
+    val skills: List<String>,
+    val hardest: List<StrainSection>,
+    val spikes: List<StrainSection>,
+) {
+    /**
+     * [strain] 对最难段为平均强度, 对突增区间为峰值
+     */
+    data class StrainSection(
+        val start: Double,
+        val end: Double,
+        val strain: Double,
+        val contribution: List<Double>,
+    )
+}
@@ -0,0 +1,19 @@
+package rosu.beatmap
+
+/**
+ * 转为 taiko/mania 的谱面没有连击信息, [newCombo], [comboIndex], [indexInCombo] 均为默认值
+ */
+data class HitObjectInfo(
+    val startTime: Double,
+    val endTime: Double,
+    val x: Float,
+    val y: Float,
+    val type: HitObjectType,
+    val newCombo: Boolean,
+    val comboIndex: Int,
+    val indexInCombo: Int,
+    /**
+     * `normal 1, whistle 2, finish 4, clap 8`
+     */
+    val hitSound: Int,
+)
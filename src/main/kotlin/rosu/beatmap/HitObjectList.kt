@@ -0,0 +1,11 @@
+package rosu.beatmap
+
+import rosu.osu.Mode
+
+/**
+ * 谱面 (可能是转谱) 的所有物件, 时间 (ms) 已按倍速换算
+ */
+data class HitObjectList(
+    val mode: Mode,
+    val objects: List<HitObjectInfo>,
+)
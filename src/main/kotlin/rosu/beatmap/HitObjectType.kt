@@ -0,0 +1,12 @@
+package rosu.beatmap
+
+enum class HitObjectType {
+    Circle,
+    Slider,
+    Spinner,
+    Hold;
+
+    companion object {
+        fun getType(i: Int) = entries[i]
+    }
+}
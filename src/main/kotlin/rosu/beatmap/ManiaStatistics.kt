@@ -0,0 +1,28 @@
+package rosu.beatmap
+
+/**
+ * mania 谱面的按键统计, 时间 (ms) 已按倍速换算
+ *
+ * - row: 同一时刻的所有物件, chord 为至少两个物件的 row
+ * - jack: 同一列在相邻的两个 row 中都有物件
+ * - [left] / [right]: 左右手各自负责的列的物件数, 奇数键时中间一列计入 [middle]
+ */
+data class ManiaStatistics(
+    val keys: Int,
+    val columns: List<Int>,
+    val notes: Int,
+    val longNotes: Int,
+    val longNoteRatio: Double,
+    val averageLongNoteLength: Double,
+    val rows: Int,
+    val chords: Int,
+    val jacks: Int,
+    val chordRatio: Double,
+    val averageRowSize: Double,
+    val jackRatio: Double,
+    val chordsPerSecond: Double,
+    val jacksPerSecond: Double,
+    val left: Int,
+    val right: Int,
+    val middle: Int,
+)
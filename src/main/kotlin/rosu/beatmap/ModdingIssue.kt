@@ -0,0 +1,39 @@
+package rosu.beatmap
+
+/**
+ * 谱面检查发现的问题
+ *
+ * [mapIndex] 为检查图组时难度的序号, [time] 为谱面时间, 不针对某个时间点的问题为 null
+ */
+data class ModdingIssue(
+    val severity: Severity,
+    val check: Check,
+    val mapIndex: Int,
+    val time: Double?,
+    val message: String,
+) {
+    enum class Severity {
+        Minor,
+        Warning,
+        Problem;
+
+        companion object {
+            fun getType(i: Int) = entries[i]
+        }
+    }
+
+    enum class Check {
+        Unsnapped,
+        ObjectInBreak,
+        Overlapping,
+        ShortDrain,
+        MissingPreview,
+        InconsistentMetadata,
+        AbnormalSliderVelocity,
+        ShortSpinner;
+
+        companion object {
+            fun getType(i: Int) = entries[i]
+        }
+    }
+}
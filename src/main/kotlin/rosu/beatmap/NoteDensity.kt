@@ -0,0 +1,17 @@
+package rosu.beatmap
+
+import rosu.osu.Mode
+
+/**
+ * 每秒物件数, 时间 (ms) 已按倍速换算
+ *
+ * [nps] 从 [startTime] (第一个物件) 开始, 每 [window] 一段
+ */
+data class NoteDensity(
+    val mode: Mode,
+    val window: Double,
+    val peakNps: Double,
+    val averageNps: Double,
+    val startTime: Double,
+    val nps: List<Double>,
+)
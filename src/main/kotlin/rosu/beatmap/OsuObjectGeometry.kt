@@ -0,0 +1,17 @@
+package rosu.beatmap
+
+/**
+ * osu! 物件经过 HR 翻转与堆叠后的位置, 时间 (ms) 已按倍速换算
+ */
+data class OsuObjectGeometry(
+    val startTime: Double,
+    val endTime: Double,
+    val x: Float,
+    val y: Float,
+    val stackHeight: Int,
+    val type: HitObjectType,
+    /**
+     * 仅滑条有值
+     */
+    val slider: SliderGeometry?,
+)
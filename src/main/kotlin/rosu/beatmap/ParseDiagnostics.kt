@@ -0,0 +1,32 @@
+package rosu.beatmap
+
+/**
+ * 解析谱面时发现的问题
+ *
+ * [formatVersion] 为文件头中的格式版本, 缺少时为 null
+ */
+data class ParseDiagnostics(
+    val formatVersion: Int?,
+    val warnings: List<ParseWarning>,
+) {
+    /**
+     * [line] 从 1 开始, 与行无关的问题为 null
+     */
+    data class ParseWarning(
+        val kind: Kind,
+        val line: Int?,
+        val message: String,
+    )
+
+    enum class Kind {
+        UnknownSection,
+        InvalidLine,
+        MissingTimingPoints,
+        NoHitObjects,
+        UnsupportedVersion;
+
+        companion object {
+            fun getType(i: Int) = entries[i]
+        }
+    }
+}
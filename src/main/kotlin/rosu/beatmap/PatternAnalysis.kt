@@ -0,0 +1,22 @@
+package rosu.beatmap
+
+/**
+ * osu! 谱面的 pattern 分类, 各项为物件数占比
+ *
+ * [longestStream] 为最长 stream 的物件数, burst 不计入, 没有 stream 时为 0;
+ * [aimRatio] 为 `aim / (aim + speed)`, 大于 0.5 偏向 aim, 反之偏向 speed
+ */
+data class PatternAnalysis(
+    val stream: Double,
+    val burst: Double,
+    val jump: Double,
+    val alt: Double,
+    val slider: Double,
+    val other: Double,
+    val longestStream: Int,
+    val streamCount: Int,
+    val burstCount: Int,
+    val aim: Double,
+    val speed: Double,
+    val aimRatio: Double,
+)
@@ -0,0 +1,22 @@
+package rosu.beatmap
+
+/**
+ * 同时可见的物件数 (不计转盘), 时间 (ms) 已按倍速换算
+ *
+ * [preempt] 为受 AR, mods 与倍速影响的缩圈时间, [fadeIn] 为 HD 的淡入时长,
+ * [timeline] 为可见数量变化的时间点
+ */
+data class ReadingAnalysis(
+    val preempt: Double,
+    val fadeIn: Double,
+    val hidden: Boolean,
+    val peak: Int,
+    val peakTime: Double,
+    val average: Double,
+    val timeline: List<VisiblePoint>,
+) {
+    data class VisiblePoint(
+        val time: Double,
+        val visible: Int,
+    )
+}
@@ -0,0 +1,23 @@
+package rosu.beatmap
+
+/**
+ * 滚动速度变化, 时间与 bpm 已按倍速换算
+ *
+ * [baseVelocity] 为最常见 bpm 且 sv 为 1 时的速度 (osu! 像素 / ms),
+ * 其余速度均为相对于它的倍数, [changes] 为速度实际变化的次数
+ */
+data class ScrollSpeedAnalysis(
+    val baseVelocity: Double,
+    val min: Double,
+    val max: Double,
+    val average: Double,
+    val changes: Int,
+    val timeline: List<ScrollPoint>,
+) {
+    data class ScrollPoint(
+        val time: Double,
+        val bpm: Double,
+        val sv: Double,
+        val speed: Double,
+    )
+}
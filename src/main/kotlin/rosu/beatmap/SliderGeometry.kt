@@ -0,0 +1,21 @@
+package rosu.beatmap
+
+/**
+ * 滑条的曲线 (折线) 与 tick, 坐标为 osu! 像素, 已包含堆叠偏移
+ */
+data class SliderGeometry(
+    val repeats: Int,
+    val path: List<Point>,
+    val ticks: List<Tick>,
+) {
+    data class Point(
+        val x: Float,
+        val y: Float,
+    )
+
+    data class Tick(
+        val time: Double,
+        val x: Float,
+        val y: Float,
+    )
+}
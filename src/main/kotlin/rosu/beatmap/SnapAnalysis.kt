@@ -0,0 +1,26 @@
+package rosu.beatmap
+
+/**
+ * 物件开始时间所在的分音, 相对于所在红线
+ *
+ * [divisors] 的 key 为分母, 例如 4 表示 1/4, 同时符合多个时计入较粗的一个
+ */
+data class SnapAnalysis(
+    val total: Int,
+    val divisors: Map<Int, Int>,
+    val unsnapped: List<UnsnappedObject>,
+) {
+    /**
+     * 某个分音的物件占比
+     */
+    fun ratio(divisor: Int): Double = if (total == 0) 0.0 else (divisors[divisor] ?: 0).toDouble() / total
+
+    /**
+     * [offset] 为与最近分音线的偏移 (ms), 正数表示偏后
+     */
+    data class UnsnappedObject(
+        val index: Int,
+        val time: Double,
+        val offset: Double,
+    )
+}
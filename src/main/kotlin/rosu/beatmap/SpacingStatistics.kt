@@ -0,0 +1,16 @@
+package rosu.beatmap
+
+/**
+ * osu! 谱面相邻物件的间距统计, [radius] 为受 mods 影响的圆圈半径
+ *
+ * - [distance]: 间距 (osu! 像素), [distanceInRadii]: 以半径为单位的间距
+ * - [angle]: 连续三个物件的夹角 (度), 180 为直线
+ * - [velocity]: 间距除以间隔 (osu! 像素 / ms), 已按倍速换算
+ */
+data class SpacingStatistics(
+    val radius: Float,
+    val distance: Distribution,
+    val distanceInRadii: Distribution,
+    val angle: Distribution,
+    val velocity: Distribution,
+)
@@ -0,0 +1,21 @@
+package rosu.beatmap
+
+/**
+ * taiko 谱面的颜色与节奏统计, 时长 (ms) 已按倍速换算
+ *
+ * 单色连续与颜色变化只在相邻的音符之间计算, 中间有鼓棒或转盘时断开
+ */
+data class TaikoStatistics(
+    val don: Int,
+    val kat: Int,
+    val finisher: Int,
+    val donRatio: Double,
+    val longestMono: Int,
+    val rolls: Int,
+    val rollDuration: Double,
+    val swells: Int,
+    val swellDuration: Double,
+    val colourChanges: Int,
+    val colourChangeRatio: Double,
+    val colourChangesPerSecond: Double,
+)
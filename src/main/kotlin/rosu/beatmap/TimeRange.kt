@@ -0,0 +1,12 @@
+package rosu.beatmap
+
+/**
+ * 时间段 (ms)
+ */
+data class TimeRange(
+    val start: Double,
+    val end: Double,
+) {
+    val duration: Double
+        get() = end - start
+}
@@ -0,0 +1,39 @@
+package rosu.beatmap
+
+/**
+ * 谱面的 bpm 与长度, 时间 (ms) 与 bpm 均已按倍速换算
+ */
+data class TimingAnalysis(
+    val bpmMin: Double,
+    val bpmMax: Double,
+    val bpmMostCommon: Double,
+    val totalLength: Double,
+    val drainLength: Double,
+    val timingPoints: List<TimingPoint>,
+    val difficultyPoints: List<DifficultyPoint>,
+    val breaks: List<TimeRange>,
+    val kiaiSections: List<TimeRange>,
+    /**
+     * 第一个物件到最后一个物件之间, 除去休息段后的游玩段
+     */
+    val drainSegments: List<TimeRange>,
+) {
+    /**
+     * 红线
+     */
+    data class TimingPoint(
+        val time: Double,
+        val beatLength: Double,
+        val bpm: Double,
+        val timeSignature: Int,
+        val omitFirstBarLine: Boolean,
+    )
+
+    /**
+     * 绿线
+     */
+    data class DifficultyPoint(
+        val time: Double,
+        val sliderVelocity: Double,
+    )
+}
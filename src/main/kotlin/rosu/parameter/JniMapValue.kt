@@ -0,0 +1,43 @@
+package rosu.parameter
+
+import rosu.osu.Mode
+
+/**
+ * 不依赖谱面文件, 直接使用 ar/od/cs/hp 数值计算谱面属性
+ */
+@Suppress("unused")
+data class JniMapValue(
+    val attr: JniMapAttr = JniMapAttr(),
+    var ar: Double = 5.0,
+    var od: Double = 5.0,
+    var cs: Double = 5.0,
+    var hp: Double = 5.0,
+) : Parameter {
+    constructor(
+        mode: Mode = Mode.Default,
+        mods: Int = 0,
+        speed: Double = -1.0,
+        ar: Double = 5.0,
+        od: Double = 5.0,
+        cs: Double = 5.0,
+        hp: Double = 5.0,
+    ) : this(
+        JniMapAttr(mode, mods, speed), ar, od, cs, hp
+    )
+
+    override fun size() = attr.size() + 8 * 4
+
+    override fun toBytes() = buffer {
+        put(attr.toBytes())
+        putDouble(ar)
+        putDouble(od)
+        putDouble(cs)
+        putDouble(hp)
+    }
+
+    var mode: Mode by attr::mode
+
+    var mods: Int by attr::mods
+
+    var speed: Double by attr::speed
+}
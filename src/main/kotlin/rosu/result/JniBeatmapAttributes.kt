@@ -0,0 +1,28 @@
+package rosu.result
+
+import rosu.osu.Mode
+
+/**
+ * 受 mods 与倍速影响后的谱面属性
+ *
+ * [hitWindows] 为各判定的区间 (ms):
+ * - osu: `300, 100, 50`
+ * - taiko: `great, ok`
+ * - catch: 无
+ * - mania: `MAX, 300, 200, 100, 50`
+ */
+class JniBeatmapAttributes {
+    var mode: Mode = Mode.Default
+    var ar: Double = 0.0
+    var od: Double = 0.0
+    var cs: Double = 0.0
+    var hp: Double = 0.0
+    var clockRate: Double = 1.0
+    var preempt: Double = 0.0
+    var radius: Double = 0.0
+    var hitWindows: List<Double> = emptyList()
+
+    override fun toString(): String {
+        return "JniBeatmapAttributes(mode=$mode, ar=$ar, od=$od, cs=$cs, hp=$hp, clockRate=$clockRate, preempt=$preempt, radius=$radius, hitWindows=$hitWindows)"
+    }
+}